@@ -1,11 +1,33 @@
 use tauri_plugin_store::StoreExt;
 
-use crate::state::AppSettingsState;
+use crate::{
+   note_capture::{
+      exporters::find_exporter,
+      models::{NoteSnippet, TagStyles},
+      tags::is_valid_tag,
+      templates::{
+         validate, validate_line_template, FOOTER_PLACEHOLDERS,
+         HEADER_PLACEHOLDERS,
+      },
+   },
+   shortcuts::{is_app_shortcut, is_same_shortcut, update_snippet_shortcuts},
+   state::AppSettingsState,
+};
 
 #[derive(serde::Serialize)]
 pub struct AppSettingsResponse {
    pub start_at_login: bool,
    pub hide_from_capture: bool,
+   pub enabled_exporters: Vec<String>,
+   pub cue_duration_ms: u64,
+   pub ffmpeg_path: Option<String>,
+   pub note_line_template: String,
+   pub rejected_note_line_template: Option<String>,
+   pub note_header_template: String,
+   pub note_footer_template: String,
+   pub tag_styles: TagStyles,
+   pub note_snippets: Vec<NoteSnippet>,
+   pub report_gap_threshold_ms: u64,
 }
 
 #[tauri::command]
@@ -16,6 +38,16 @@ pub async fn get_app_settings(
       Ok(AppSettingsResponse {
          start_at_login: state.start_at_login,
          hide_from_capture: state.hide_from_capture,
+         enabled_exporters: state.enabled_exporters.clone(),
+         cue_duration_ms: state.cue_duration_ms,
+         ffmpeg_path: state.ffmpeg_path.clone(),
+         note_line_template: state.note_line_template.clone(),
+         rejected_note_line_template: state.rejected_note_line_template.clone(),
+         note_header_template: state.note_header_template.clone(),
+         note_footer_template: state.note_footer_template.clone(),
+         tag_styles: state.tag_styles.clone(),
+         note_snippets: state.note_snippets.clone(),
+         report_gap_threshold_ms: state.report_gap_threshold_ms,
       })
    } else {
       Err("Failed to acquire lock on App Settings".to_string())
@@ -61,3 +93,241 @@ pub async fn update_hide_from_capture(
 
    Ok(())
 }
+
+#[tauri::command]
+pub async fn update_enabled_exporters(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   exporter_ids: Vec<String>,
+) -> Result<(), String> {
+   let mut enabled_exporters: Vec<String> = Vec::new();
+   for id in exporter_ids {
+      if find_exporter(&id).is_none() {
+         return Err(format!("Unknown note exporter '{}'", id));
+      }
+
+      if !enabled_exporters.contains(&id) {
+         enabled_exporters.push(id);
+      }
+   }
+
+   // The note file is only removed after export, so something must hold
+   // the notes
+   if enabled_exporters.is_empty() {
+      return Err("Select at least one note exporter".to_string());
+   }
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("enabled_exporters", serde_json::json!(enabled_exporters));
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.enabled_exporters = enabled_exporters;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_cue_duration(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   cue_duration_ms: u64,
+) -> Result<(), String> {
+   if cue_duration_ms == 0 {
+      return Err("Cue duration must be greater than zero".to_string());
+   }
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("cue_duration_ms", cue_duration_ms);
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.cue_duration_ms = cue_duration_ms;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_report_gap_threshold(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   report_gap_threshold_ms: u64,
+) -> Result<(), String> {
+   if report_gap_threshold_ms == 0 {
+      return Err("Gap threshold must be greater than zero".to_string());
+   }
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("report_gap_threshold_ms", report_gap_threshold_ms);
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.report_gap_threshold_ms = report_gap_threshold_ms;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_ffmpeg_path(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   ffmpeg_path: Option<String>,
+) -> Result<(), String> {
+   let ffmpeg_path = ffmpeg_path.filter(|path| !path.trim().is_empty());
+   if let Some(path) = &ffmpeg_path {
+      if !std::path::Path::new(path).is_file() {
+         return Err(format!("FFmpeg not found at '{}'", path));
+      }
+   }
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("ffmpeg_path", serde_json::json!(ffmpeg_path));
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.ffmpeg_path = ffmpeg_path;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_note_templates(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   line_template: String,
+   header_template: String,
+   footer_template: String,
+) -> Result<(), String> {
+   if line_template.trim().is_empty() {
+      return Err("Line template cannot be empty".to_string());
+   }
+
+   validate_line_template(&line_template)
+      .map_err(|e| format!("Line template: {}", e))?;
+   validate(&header_template, HEADER_PLACEHOLDERS)
+      .map_err(|e| format!("Header template: {}", e))?;
+   validate(&footer_template, FOOTER_PLACEHOLDERS)
+      .map_err(|e| format!("Footer template: {}", e))?;
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("note_line_template", line_template.clone());
+   store.set("note_header_template", header_template.clone());
+   store.set("note_footer_template", footer_template.clone());
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.note_line_template = line_template;
+      state.rejected_note_line_template = None;
+      state.note_header_template = header_template;
+      state.note_footer_template = footer_template;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_tag_styles(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   tag_styles: TagStyles,
+) -> Result<(), String> {
+   for (name, style) in &tag_styles {
+      if !is_valid_tag(name) {
+         return Err(format!(
+            "Invalid tag '{}', use lowercase letters, digits, '-' or '_'",
+            name
+         ));
+      }
+
+      let is_hex_color = style.color.len() == 7
+         && style.color.starts_with('#')
+         && style.color[1..].chars().all(|c| c.is_ascii_hexdigit());
+      if !is_hex_color {
+         return Err(format!(
+            "Invalid color '{}' for tag '{}', expected #rrggbb",
+            style.color, name
+         ));
+      }
+   }
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("tag_styles", serde_json::json!(tag_styles));
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.tag_styles = tag_styles;
+   }
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn update_note_snippets(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+   note_snippets: Vec<NoteSnippet>,
+) -> Result<(), String> {
+   let mut shortcuts: Vec<tauri_plugin_global_shortcut::Shortcut> = Vec::new();
+   for snippet in &note_snippets {
+      if snippet.text.trim().is_empty() {
+         return Err("Snippet text cannot be empty".to_string());
+      }
+
+      let shortcut = snippet.shortcut.parse().map_err(|e| {
+         format!("Invalid shortcut '{}': {}", snippet.shortcut, e)
+      })?;
+
+      if is_app_shortcut(&shortcut) {
+         return Err(format!(
+            "Shortcut '{}' is already used by Clip Mark",
+            snippet.shortcut
+         ));
+      }
+      if shortcuts
+         .iter()
+         .any(|other| is_same_shortcut(other, &shortcut))
+      {
+         return Err(format!(
+            "Shortcut '{}' is used by more than one snippet",
+            snippet.shortcut
+         ));
+      }
+      shortcuts.push(shortcut);
+   }
+
+   let previous = app_settings
+      .lock()
+      .map(|state| state.note_snippets.clone())
+      .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+   // Snippets are only saved once all their shortcuts are registered
+   update_snippet_shortcuts(&app_handle, &previous, &note_snippets)?;
+
+   let store = app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .expect("Failed to load App Settings store");
+
+   store.set("note_snippets", serde_json::json!(note_snippets));
+
+   if let Ok(mut state) = app_settings.lock() {
+      state.note_snippets = note_snippets;
+   }
+
+   Ok(())
+}
@@ -0,0 +1,10 @@
+use strum::{AsRefStr, Display, EnumString};
+
+#[derive(
+   EnumString, AsRefStr, Display, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+pub enum AppSettingsEvents {
+   /// The saved line template cannot be read back, the default is used
+   #[strum(serialize = "app_settings:line_template_rejected")]
+   LineTemplateRejected,
+}
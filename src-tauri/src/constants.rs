@@ -44,4 +44,6 @@ pub enum Store {
    AppSettings,
    #[strum(serialize = "obs-server-config.json")]
    ObsServerConfig,
+   #[strum(serialize = "recording-state.json")]
+   RecordingState,
 }
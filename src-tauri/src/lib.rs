@@ -1,8 +1,10 @@
 mod app_settings;
 mod constants;
 mod note_capture;
+mod notes_library;
 mod obs_websocket_configuration;
 mod obs_websocket_connection;
+mod orphaned_notes;
 mod positioner;
 mod shortcuts;
 mod state;
@@ -11,19 +13,21 @@ mod window_utilities;
 
 use std::sync::Mutex;
 
-use log::info;
+use log::{info, warn};
 use tauri::{Emitter, Manager};
 use tauri_plugin_positioner::{Position, WindowExt};
 use tauri_plugin_store::StoreExt;
 use tauri_plugin_updater::UpdaterExt;
 
 use crate::{
-   app_settings::service::{update_autostart, update_hide_from_capture},
+   app_settings::{
+      models::AppSettingsEvents,
+      service::{update_autostart, update_hide_from_capture},
+   },
    constants::{WindowEvent, WindowLabel},
+   note_capture::templates::validate_line_template,
    positioner::WindowTrayExt,
-   state::{
-      AppSettingsState, GlobalState, RecordingStateMutex, ServerConfigState,
-   },
+   state::{AppSettingsState, GlobalState, RecordingState, ServerConfigState},
    system_tray::service::init_system_tray,
    window_utilities::WindowUtilitiesExt,
 };
@@ -47,17 +51,36 @@ pub fn run() {
       crate::window_utilities::commands::fullscreen_window,
       crate::window_utilities::commands::hide_window,
       crate::note_capture::commands::capture_note,
+      crate::note_capture::commands::list_notes,
+      crate::note_capture::commands::edit_note,
+      crate::note_capture::commands::delete_note,
+      crate::note_capture::commands::redo_last_note,
+      crate::note_capture::commands::list_pending_markers,
+      crate::note_capture::commands::get_note_exporters,
+      crate::note_capture::commands::get_tags,
+      crate::notes_library::commands::search_notes,
+      crate::notes_library::commands::scan_notes_library,
+      crate::notes_library::commands::reveal_recording,
+      crate::note_capture::commands::convert_note_file,
+      crate::orphaned_notes::commands::list_orphaned_notes,
+      crate::orphaned_notes::commands::attach_orphaned_notes,
+      crate::orphaned_notes::commands::archive_orphaned_notes,
       crate::shortcuts::commands::get_shortcuts,
       crate::app_settings::commands::get_app_settings,
       crate::app_settings::commands::update_start_at_login,
       crate::app_settings::commands::update_hide_from_capture,
+      crate::app_settings::commands::update_enabled_exporters,
+      crate::app_settings::commands::update_cue_duration,
+      crate::app_settings::commands::update_ffmpeg_path,
+      crate::app_settings::commands::update_report_gap_threshold,
+      crate::app_settings::commands::update_note_templates,
+      crate::app_settings::commands::update_tag_styles,
+      crate::app_settings::commands::update_note_snippets,
       crate::window_utilities::commands::resize_window,
    ]);
 
    // State
-   app_builder = app_builder
-      .manage(GlobalState::default())
-      .manage(RecordingStateMutex::default());
+   app_builder = app_builder.manage(GlobalState::default());
 
    // Plugins
    app_builder = app_builder
@@ -112,12 +135,21 @@ fn setup_stores(
       .store(crate::constants::Store::AppSettings.as_ref())
       .expect("Failed to load App Settings store");
 
+   let recording_state_store = app
+      .store(crate::constants::Store::RecordingState.as_ref())
+      .expect("Failed to load Recording State store");
+
    app.manage(Mutex::new(ServerConfigState::from_store(
       &server_config_store,
    )));
    app.manage(Mutex::new(AppSettingsState::from_store(
       &app_settings_store,
    )));
+   // Restored so a restart mid-recording keeps the same note file, and
+   // reconciled with OBS once connected
+   app.manage(Mutex::new(RecordingState::from_store(
+      &recording_state_store,
+   )));
 
    Ok(())
 }
@@ -190,6 +222,22 @@ fn init_app_settings(app_handle: tauri::AppHandle) {
 
    update_hide_from_capture(&app_handle, app_settings.hide_from_capture);
    update_autostart(&app_handle, app_settings.start_at_login);
+
+   if let Some(template) = &app_settings.rejected_note_line_template {
+      let error = validate_line_template(template)
+         .err()
+         .map(|err| err.to_string())
+         .unwrap_or_default();
+      warn!(
+         "Saved note line template '{}' cannot be read back, using the \
+          default: {}",
+         template, error
+      );
+      let _ = app_handle.emit(
+         AppSettingsEvents::LineTemplateRejected.as_ref(),
+         format!("Note line template '{}' was reset: {}", template, error),
+      );
+   }
 }
 
 /// Spawn OBS Websocket connection
@@ -243,6 +291,15 @@ async fn update(app: &tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
          .await?;
 
       println!("Update installed");
+
+      // Restarting skips the store's pending debounced write
+      if let Ok(store) =
+         app.store(crate::constants::Store::RecordingState.as_ref())
+      {
+         if let Err(err) = store.save() {
+            warn!("Failed to save recording state: {}", err);
+         }
+      }
       app.restart();
    }
 
@@ -1,7 +1,22 @@
-use chrono::{DateTime, Duration, TimeDelta, Utc};
-use tauri::Manager;
+use chrono::{DateTime, Utc};
+use log::warn;
+use tauri::{Emitter, Manager};
 
-use crate::state::RecordingStateMutex;
+use crate::{
+   note_capture::{
+      exporters::{
+         export_notes, exporters, find_exporter, sibling_recording,
+         ExportContext, TEXT_ID,
+      },
+      models::{Note, NoteExporterDetails, TagDetails, UndoneNote},
+      parser::{parse_notes, NoteFileTemplates},
+      tags::{parse_tags, remember_tags, used_tags},
+      templates::render_line,
+   },
+   obs_websocket_connection::models::RecordingEvents,
+   state::{AppSettingsState, RecordingState, RecordingStateMutex},
+   GlobalState,
+};
 
 #[tauri::command]
 pub async fn capture_note(
@@ -9,51 +24,612 @@ pub async fn capture_note(
    note: String,
    date: DateTime<Utc>,
 ) -> Result<(), String> {
+   if let (Some(mark), text) = range_mark(&note) {
+      return mark_range(&app_handle, mark, text, date);
+   }
+
    let recording_state = app_handle.state::<RecordingStateMutex>();
-   let note_timestamp = date.timestamp_millis();
+   let line_template = current_line_template(&app_handle)?;
+   let scene = current_scene(&app_handle);
 
-   if let Ok(state) = recording_state.lock() {
+   if let Ok(mut state) = recording_state.lock() {
       if !state.recording_status.active {
          return Err("Recording is not active".to_string());
       }
 
-      if let Some(recording_start) = state.recording_start {
-         let actual_timecode_ms = if state.recording_status.paused {
-            // If paused, use the pause time as the note timecode
-            if let Some(pause_start) = state.pause_start {
-               (pause_start - recording_start)
-                  - state.accumulated_pause_duration
-            } else {
-               return Err("Paused but pause time not set".to_string());
+      let note = Note {
+         id: state.next_note_id + 1,
+         timecode_ms: note_timecode(&state, date.timestamp_millis())?,
+         end_timecode_ms: None,
+         created_at: Some(date),
+         scene,
+         tags: parse_tags(&note),
+         text: note,
+      };
+
+      let tags = note.tags.clone();
+      append_note(&mut state, note, &line_template)?;
+      state.save(&app_handle);
+      remember_tags(&app_handle, &tags);
+   } else {
+      return Err("Failed to lock recording state".to_string());
+   }
+
+   Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeMark {
+   In,
+   Out,
+}
+
+/// Split an `in:`/`out:` prefix off note text, returning the rest trimmed
+fn range_mark(text: &str) -> (Option<RangeMark>, &str) {
+   let trimmed = text.trim_start();
+
+   for (prefix, mark) in [("in:", RangeMark::In), ("out:", RangeMark::Out)] {
+      if let Some(rest) = trimmed
+         .get(..prefix.len())
+         .filter(|start| start.eq_ignore_ascii_case(prefix))
+         .map(|_| &trimmed[prefix.len()..])
+      {
+         return (Some(mark), rest.trim());
+      }
+   }
+
+   (None, text)
+}
+
+/// Open a range note at the current timecode, or close the open one.
+/// Marking in while a range is open closes it where the new one starts.
+/// Marking out with no range open adds a point note instead, so the text
+/// is not lost.
+pub fn mark_range(
+   app_handle: &tauri::AppHandle,
+   mark: RangeMark,
+   text: &str,
+   date: DateTime<Utc>,
+) -> Result<(), String> {
+   let line_template = current_line_template(app_handle)?;
+   let scene = current_scene(app_handle);
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   if !state.recording_status.active {
+      return Err("Recording is not active".to_string());
+   }
+
+   let timecode_ms = note_timecode(&state, date.timestamp_millis())?;
+
+   // Kept to undo the close if the note file cannot be rewritten
+   let open_range_id = state.open_range_id;
+   let open_range = open_range_id
+      .and_then(|id| state.notes.iter().find(|note| note.id == id).cloned());
+
+   // Text given on mark in belongs to the new range only
+   let closing_text = if mark == RangeMark::Out { text } else { "" };
+   let closed = close_open_range(&mut state, timecode_ms, closing_text);
+
+   if closed.is_some() {
+      if let Err(err) = rewrite_note_file(&state, &line_template) {
+         if let Some(previous) = open_range {
+            if let Some(note) =
+               state.notes.iter_mut().find(|note| note.id == previous.id)
+            {
+               *note = previous;
             }
-         } else {
-            // If recording, calculate based on current time
-            let total_elapsed = note_timestamp - recording_start;
-            total_elapsed - state.accumulated_pause_duration
-         };
-
-         let timecode = Duration::milliseconds(actual_timecode_ms);
-         if let Some(note_file_path) = &state.note_file_path {
-            write_note_to_file(
-               std::path::Path::new(note_file_path),
-               timecode,
-               &note,
-            )?;
          }
+         state.open_range_id = open_range_id;
+         return Err(err);
+      }
+   }
+
+   if let (RangeMark::Out, Some(closed)) = (mark, &closed) {
+      state.save(app_handle);
+      remember_tags(app_handle, &closed.tags);
+      return Ok(());
+   }
+
+   let note = Note {
+      id: state.next_note_id + 1,
+      timecode_ms,
+      end_timecode_ms: None,
+      created_at: Some(date),
+      scene,
+      tags: parse_tags(text),
+      text: text.to_string(),
+   };
+
+   let added = note.clone();
+   append_note(&mut state, note, &line_template)?;
+   if mark == RangeMark::In {
+      state.open_range_id = Some(added.id);
+   }
+   state.save(app_handle);
+   remember_tags(app_handle, &added.tags);
+
+   if mark == RangeMark::Out {
+      warn!("No range open to mark out, added a point note instead");
+      let _ = app_handle.emit(RecordingEvents::NoRangeOpen.as_ref(), added);
+   }
+
+   Ok(())
+}
+
+/// Add a note without text at the current timecode, for the text to be
+/// filled in later from the pending markers
+pub fn quick_marker(app_handle: &tauri::AppHandle) -> Result<(), String> {
+   add_note_now(app_handle, "")
+}
+
+/// Add a snippet's text as a note at the current timecode
+pub fn capture_snippet(
+   app_handle: &tauri::AppHandle,
+   text: &str,
+) -> Result<(), String> {
+   add_note_now(app_handle, text)
+}
+
+/// Add a note for a shortcut press, no window having been opened
+fn add_note_now(
+   app_handle: &tauri::AppHandle,
+   text: &str,
+) -> Result<(), String> {
+   let line_template = current_line_template(app_handle)?;
+   let scene = current_scene(app_handle);
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   if !state.recording_status.active {
+      return Err("Recording is not active".to_string());
+   }
+
+   let date = Utc::now();
+   let note = Note {
+      id: state.next_note_id + 1,
+      timecode_ms: note_timecode(&state, date.timestamp_millis())?,
+      end_timecode_ms: None,
+      created_at: Some(date),
+      scene,
+      tags: parse_tags(text),
+      text: text.to_string(),
+   };
+
+   let added = note.clone();
+   append_note(&mut state, note, &line_template)?;
+   state.save(app_handle);
+   remember_tags(app_handle, &added.tags);
+
+   // Lets the overlay pulse, as no window opens for the note
+   let _ = app_handle.emit(RecordingEvents::MarkerAdded.as_ref(), added);
+
+   Ok(())
+}
+
+/// Notes of the current recording still waiting for their text
+#[tauri::command]
+pub async fn list_pending_markers(
+   recording_state: tauri::State<'_, RecordingStateMutex>,
+) -> Result<Vec<Note>, String> {
+   let state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   Ok(state
+      .notes
+      .iter()
+      .filter(|note| note.text.trim().is_empty())
+      .cloned()
+      .collect())
+}
+
+/// End the open range note at `timecode_ms`, adding any text given on mark
+/// out to it. Returns the closed note.
+pub fn close_open_range(
+   state: &mut RecordingState,
+   timecode_ms: i64,
+   text: &str,
+) -> Option<Note> {
+   let id = state.open_range_id.take()?;
+   let note = state.notes.iter_mut().find(|note| note.id == id)?;
+
+   note.end_timecode_ms = Some(timecode_ms.max(note.timecode_ms));
+   if !text.is_empty() {
+      if !note.text.is_empty() {
+         note.text.push(' ');
+      }
+      note.text.push_str(text);
+      note.tags = parse_tags(&note.text);
+   }
+
+   Some(note.clone())
+}
+
+/// Position in the recording for a note taken at `note_timestamp`,
+/// excluding paused time
+fn note_timecode(
+   state: &RecordingState,
+   note_timestamp: i64,
+) -> Result<i64, String> {
+   let Some(recording_start) = state.recording_start else {
+      return Err("Recording start time is not set".to_string());
+   };
+
+   if state.recording_status.paused {
+      // If paused, use the pause time as the note timecode
+      if let Some(pause_start) = state.pause_start {
+         Ok((pause_start - recording_start) - state.accumulated_pause_duration)
       } else {
-         return Err("Recording start time is not set".to_string());
+         Err("Paused but pause time not set".to_string())
       }
    } else {
-      return Err("Failed to lock recording state".to_string());
+      // If recording, calculate based on current time
+      let total_elapsed = note_timestamp - recording_start;
+      Ok(total_elapsed - state.accumulated_pause_duration)
+   }
+}
+
+/// Write a new note to the end of the note file and the note list
+fn append_note(
+   state: &mut RecordingState,
+   note: Note,
+   line_template: &str,
+) -> Result<(), String> {
+   if let Some(note_file_path) = &state.note_file_path {
+      write_note_to_file(
+         std::path::Path::new(note_file_path),
+         &render_line(line_template, &note, state.notes.len() + 1),
+      )?;
    }
 
+   state.next_note_id = note.id;
+   state.notes.push(note);
+
    Ok(())
 }
 
+#[tauri::command]
+pub async fn list_notes(
+   recording_state: tauri::State<'_, RecordingStateMutex>,
+) -> Result<Vec<Note>, String> {
+   let state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   Ok(state.notes.clone())
+}
+
+#[tauri::command]
+pub async fn edit_note(
+   app_handle: tauri::AppHandle,
+   id: u64,
+   text: String,
+) -> Result<Note, String> {
+   if text.trim().is_empty() {
+      return Err("Note text cannot be empty".to_string());
+   }
+
+   let line_template = current_line_template(&app_handle)?;
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   let note = state
+      .notes
+      .iter_mut()
+      .find(|note| note.id == id)
+      .ok_or_else(|| format!("Note {} not found", id))?;
+   let previous = note.clone();
+   note.tags = parse_tags(&text);
+   note.text = text;
+   let edited = note.clone();
+
+   if let Err(err) = rewrite_note_file(&state, &line_template) {
+      // Keep the list in step with the file
+      if let Some(note) = state.notes.iter_mut().find(|note| note.id == id) {
+         *note = previous;
+      }
+      return Err(err);
+   }
+
+   state.save(&app_handle);
+   remember_tags(&app_handle, &edited.tags);
+
+   Ok(edited)
+}
+
+#[tauri::command]
+pub async fn delete_note(
+   app_handle: tauri::AppHandle,
+   id: u64,
+) -> Result<(), String> {
+   let line_template = current_line_template(&app_handle)?;
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   let index = state
+      .notes
+      .iter()
+      .position(|note| note.id == id)
+      .ok_or_else(|| format!("Note {} not found", id))?;
+   let removed = state.notes.remove(index);
+   let open_range_id = state.open_range_id;
+   if open_range_id == Some(id) {
+      state.open_range_id = None;
+   }
+
+   if let Err(err) = rewrite_note_file(&state, &line_template) {
+      state.notes.insert(index, removed);
+      state.open_range_id = open_range_id;
+      return Err(err);
+   }
+
+   state.save(&app_handle);
+
+   Ok(())
+}
+
+/// How long an undone note can be brought back
+const REDO_WINDOW_MS: i64 = 10_000;
+
+/// Remove the most recent note of the active recording, keeping it around
+/// for the redo window
+pub fn undo_last_note(app_handle: &tauri::AppHandle) -> Result<(), String> {
+   let line_template = current_line_template(app_handle)?;
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   if !state.recording_status.active {
+      return Err("Recording is not active".to_string());
+   }
+
+   let note = state
+      .notes
+      .pop()
+      .ok_or_else(|| "No note to undo".to_string())?;
+   let was_open_range = state.open_range_id == Some(note.id);
+   if was_open_range {
+      state.open_range_id = None;
+   }
+
+   if let Err(err) = rewrite_note_file(&state, &line_template) {
+      if was_open_range {
+         state.open_range_id = Some(note.id);
+      }
+      state.notes.push(note);
+      return Err(err);
+   }
+
+   let undone = UndoneNote {
+      note,
+      redo_expires_at: Utc::now().timestamp_millis() + REDO_WINDOW_MS,
+      was_open_range,
+   };
+   let _ = app_handle.emit(RecordingEvents::NoteUndone.as_ref(), &undone);
+   state.undone_note = Some(undone);
+   state.save(app_handle);
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn redo_last_note(
+   app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+   let line_template = current_line_template(&app_handle)?;
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let mut state = recording_state
+      .lock()
+      .map_err(|_| "Failed to lock recording state".to_string())?;
+
+   let undone = state
+      .undone_note
+      .take()
+      .filter(|undone| undone.redo_expires_at >= Utc::now().timestamp_millis())
+      .ok_or_else(|| "No note to restore".to_string())?;
+
+   // Notes taken since the undo stay after the restored one
+   let index = state
+      .notes
+      .iter()
+      .position(|note| note.id > undone.note.id)
+      .unwrap_or(state.notes.len());
+   state.notes.insert(index, undone.note.clone());
+
+   // The range is open again unless another one was marked in since, in
+   // which case it is kept as a point note
+   let reopens_range = undone.was_open_range && state.open_range_id.is_none();
+   if reopens_range {
+      state.open_range_id = Some(undone.note.id);
+   }
+
+   if let Err(err) = rewrite_note_file(&state, &line_template) {
+      state.notes.remove(index);
+      if reopens_range {
+         state.open_range_id = None;
+      }
+      state.undone_note = Some(undone);
+      return Err(err);
+   }
+
+   state.save(&app_handle);
+   let _ =
+      app_handle.emit(RecordingEvents::NoteRestored.as_ref(), &undone.note);
+
+   Ok(())
+}
+
+#[tauri::command]
+pub async fn get_note_exporters(
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+) -> Result<Vec<NoteExporterDetails>, String> {
+   let state = app_settings
+      .lock()
+      .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+   Ok(exporters()
+      .iter()
+      .map(|exporter| NoteExporterDetails {
+         id: exporter.id().to_string(),
+         name: exporter.name().to_string(),
+         description: exporter.description().to_string(),
+         enabled: state.enabled_exporters.iter().any(|id| id == exporter.id()),
+      })
+      .collect())
+}
+
+/// Re-export an existing note file with the given exporters, returning
+/// export warnings and lines that could not be read
+#[tauri::command]
+pub async fn convert_note_file(
+   app_handle: tauri::AppHandle,
+   note_file_path: String,
+   exporter_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+   if let Some(id) = exporter_ids.iter().find(|id| find_exporter(id).is_none())
+   {
+      return Err(format!("Unknown exporter: {}", id));
+   }
+
+   let note_file_path = std::path::Path::new(&note_file_path);
+   let contents = std::fs::read_to_string(note_file_path)
+      .map_err(|e| format!("Failed to read note file: {}", e))?;
+   let settings = app_handle
+      .state::<std::sync::Mutex<AppSettingsState>>()
+      .lock()
+      .map(|settings| settings.clone())
+      .map_err(|_| "Failed to lock app settings".to_string())?;
+   let parsed =
+      parse_notes(&contents, &NoteFileTemplates::from_settings(&settings));
+
+   if parsed.notes.is_empty() {
+      return Err("No notes found in note file".to_string());
+   }
+
+   // Sidecars go next to the recording, or the note file when it is gone
+   let output_path = sibling_recording(note_file_path)
+      .unwrap_or_else(|| note_file_path.to_path_buf());
+
+   // The text exporter moves the note file, which is already in place
+   let exporter_ids: Vec<String> = exporter_ids
+      .into_iter()
+      .filter(|id| id != TEXT_ID)
+      .collect();
+
+   let summary = export_notes(
+      &ExportContext::for_note_file(
+         &app_handle,
+         &output_path,
+         note_file_path,
+         &parsed.notes,
+         &settings,
+      ),
+      &exporter_ids,
+   );
+
+   Ok(parsed
+      .errors
+      .iter()
+      .map(|err| err.to_string())
+      .chain(summary.warnings)
+      .collect())
+}
+
+/// Tags for autocompletion, most recently used first followed by styled
+/// tags that have not been used yet
+#[tauri::command]
+pub async fn get_tags(
+   app_handle: tauri::AppHandle,
+   app_settings: tauri::State<'_, std::sync::Mutex<AppSettingsState>>,
+) -> Result<Vec<TagDetails>, String> {
+   let tag_styles = app_settings
+      .lock()
+      .map(|settings| settings.tag_styles.clone())
+      .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+   let mut names = used_tags(&app_handle);
+   for name in tag_styles.keys() {
+      if !names.contains(name) {
+         names.push(name.clone());
+      }
+   }
+
+   Ok(names
+      .into_iter()
+      .map(|name| {
+         let style = tag_styles.get(&name);
+         TagDetails {
+            color: style.map(|style| style.color.clone()),
+            description: style.map(|style| style.description.clone()),
+            name,
+         }
+      })
+      .collect())
+}
+
+fn current_scene(app_handle: &tauri::AppHandle) -> Option<String> {
+   app_handle
+      .state::<GlobalState>()
+      .current_scene
+      .lock()
+      .ok()
+      .and_then(|scene| scene.clone())
+}
+
+fn current_line_template(
+   app_handle: &tauri::AppHandle,
+) -> Result<String, String> {
+   app_handle
+      .state::<std::sync::Mutex<AppSettingsState>>()
+      .lock()
+      .map(|settings| settings.note_line_template.clone())
+      .map_err(|_| "Failed to lock app settings".to_string())
+}
+
+/// Replace the note file with the current note list. The new contents are
+/// written next to it first, so a crash never leaves a half-written file.
+pub fn rewrite_note_file(
+   state: &RecordingState,
+   line_template: &str,
+) -> Result<(), String> {
+   let Some(note_file_path) = &state.note_file_path else {
+      return Ok(());
+   };
+
+   let mut contents = String::new();
+   if !state.note_file_header.is_empty() {
+      contents.push_str(&state.note_file_header);
+      contents.push('\n');
+   }
+   for (index, note) in state.notes.iter().enumerate() {
+      contents.push_str(&render_line(line_template, note, index + 1));
+      contents.push('\n');
+   }
+
+   let note_file_path = std::path::Path::new(note_file_path);
+   let temp_path = note_file_path.with_extension("txt.tmp");
+
+   std::fs::write(&temp_path, contents)
+      .map_err(|e| format!("Failed to write note file: {}", e))?;
+   std::fs::rename(&temp_path, note_file_path).map_err(|e| {
+      let _ = std::fs::remove_file(&temp_path);
+      format!("Failed to replace note file: {}", e)
+   })
+}
+
 fn write_note_to_file(
    file_path: &std::path::Path,
-   timecode: TimeDelta,
-   note: &str,
+   line: &str,
 ) -> Result<(), String> {
    use std::fs::OpenOptions;
    use std::io::Write;
@@ -64,16 +640,7 @@ fn write_note_to_file(
       .open(file_path)
       .map_err(|e| format!("Failed to open note file: {}", e))?;
 
-   let hours = timecode.num_hours();
-   let minutes = timecode.num_minutes() % 60;
-   let seconds = timecode.num_seconds() % 60;
-   let milliseconds = timecode.num_milliseconds() % 1000;
-   let formatted_timecode = format!(
-      "{:02}:{:02}:{:02}.{:03}",
-      hours, minutes, seconds, milliseconds
-   );
-
-   writeln!(file, "[{}] {}", formatted_timecode, note)
+   writeln!(file, "{}", line)
       .map_err(|e| format!("Failed to write note to file: {}", e))?;
 
    Ok(())
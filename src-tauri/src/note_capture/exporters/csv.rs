@@ -0,0 +1,82 @@
+use std::fmt::Write;
+
+use super::{ExportContext, ExportOutcome, NoteExporter};
+use crate::note_capture::models::Note;
+
+const HEADER: [&str; 6] = [
+   "timecode",
+   "seconds",
+   "wall_clock",
+   "text",
+   "tags",
+   "end_timecode",
+];
+
+pub struct CsvExporter;
+
+impl NoteExporter for CsvExporter {
+   fn id(&self) -> &'static str {
+      "csv"
+   }
+
+   fn name(&self) -> &'static str {
+      "CSV"
+   }
+
+   fn description(&self) -> &'static str {
+      "A spreadsheet row per note (.csv)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      super::write_sidecar(context.output_path, "csv", &render(context.notes))
+   }
+}
+
+fn render(notes: &[Note]) -> String {
+   let mut csv = String::new();
+   write_row(&mut csv, &HEADER.map(String::from));
+
+   for note in notes {
+      write_row(
+         &mut csv,
+         &[
+            super::format_timecode(note.timecode_ms),
+            format!("{:.3}", note.timecode_ms.max(0) as f64 / 1000.0),
+            note
+               .created_at
+               .map(|date| date.to_rfc3339())
+               .unwrap_or_default(),
+            note.text.clone(),
+            note.tags.join(" "),
+            note
+               .end_timecode_ms
+               .map(super::format_timecode)
+               .unwrap_or_default(),
+         ],
+      );
+   }
+
+   csv
+}
+
+/// Rows end with CRLF as per RFC 4180, which spreadsheet apps expect
+fn write_row(csv: &mut String, fields: &[String]) {
+   let row = fields
+      .iter()
+      .map(|field| escape(field))
+      .collect::<Vec<_>>()
+      .join(",");
+   let _ = write!(csv, "{}\r\n", row);
+}
+
+fn escape(field: &str) -> String {
+   if field.contains([',', '"', '\n', '\r']) {
+      format!("\"{}\"", field.replace('"', "\"\""))
+   } else {
+      field.to_string()
+   }
+}
@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use super::{
+   marker_frames, tag_color, ExportContext, ExportOutcome, NoteExporter,
+};
+use crate::{
+   note_capture::models::{Note, TagStyles},
+   obs_websocket_connection::models::FrameRate,
+};
+
+/// Resolve timelines start at 01:00:00:00 unless changed by the user
+const TIMELINE_START_HOURS: i64 = 1;
+
+/// Resolve marker colors, matched against tag colors
+const MARKER_COLORS: &[(&str, [u8; 3])] = &[
+   ("Blue", [0x2f, 0x80, 0xed]),
+   ("Cyan", [0x00, 0xce, 0xd1]),
+   ("Green", [0x00, 0xa8, 0x00]),
+   ("Yellow", [0xf0, 0xdd, 0x00]),
+   ("Red", [0xe6, 0x1e, 0x1e]),
+   ("Pink", [0xff, 0x69, 0xb4]),
+   ("Purple", [0x80, 0x00, 0xc8]),
+   ("Fuchsia", [0xc8, 0x00, 0xc8]),
+   ("Rose", [0xff, 0x96, 0xaa]),
+   ("Lavender", [0xb4, 0xa0, 0xe6]),
+   ("Sky", [0x8c, 0xc8, 0xf0]),
+   ("Mint", [0x96, 0xe6, 0xb4]),
+   ("Lemon", [0xf0, 0xf0, 0x8c]),
+   ("Sand", [0xc8, 0xaa, 0x78]),
+   ("Cocoa", [0x78, 0x50, 0x32]),
+   ("Cream", [0xfa, 0xf0, 0xdc]),
+];
+
+pub struct EdlExporter;
+
+impl NoteExporter for EdlExporter {
+   fn id(&self) -> &'static str {
+      "edl"
+   }
+
+   fn name(&self) -> &'static str {
+      "DaVinci Resolve EDL"
+   }
+
+   fn description(&self) -> &'static str {
+      "Timeline markers for DaVinci Resolve (.edl)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let contents = render(
+         &context.recording_name(),
+         context.notes,
+         context.frame_rate,
+         &context.settings.tag_styles,
+      );
+      super::write_sidecar(context.output_path, "edl", &contents)
+   }
+}
+
+/// One event per note with the note as a Resolve marker, colored after its
+/// first styled tag and spanning its range if it has one
+fn render(
+   title: &str,
+   notes: &[Note],
+   frame_rate: FrameRate,
+   tag_styles: &TagStyles,
+) -> String {
+   let mut edl = String::new();
+   let _ = writeln!(edl, "TITLE: {}", title);
+   let _ = writeln!(edl, "FCM: NON-DROP FRAME");
+   let _ = writeln!(edl);
+
+   let start_frame = TIMELINE_START_HOURS * 3600 * frame_rate.timebase() as i64;
+
+   for (index, note) in notes.iter().enumerate() {
+      let frame = start_frame + frame_rate.frames_from_ms(note.timecode_ms);
+      let record_in = format_timecode(frame, frame_rate);
+      let duration = marker_frames(note, frame_rate);
+      let record_out = format_timecode(frame + duration, frame_rate);
+
+      let _ = writeln!(
+         edl,
+         "{:03}  001      V     C        {} {} {} {}  ",
+         index + 1,
+         record_in,
+         record_out,
+         record_in,
+         record_out
+      );
+      let _ = writeln!(
+         edl,
+         " |C:ResolveColor{} |M:{} |D:{}",
+         tag_color(note, tag_styles, MARKER_COLORS).unwrap_or("Blue"),
+         marker_name(&note.text),
+         duration
+      );
+      let _ = writeln!(edl);
+   }
+
+   edl
+}
+
+/// Non-drop-frame `HH:MM:SS:FF` counted at the nominal timebase
+fn format_timecode(frame: i64, frame_rate: FrameRate) -> String {
+   let timebase = frame_rate.timebase() as i64;
+   let total_seconds = frame / timebase;
+
+   format!(
+      "{:02}:{:02}:{:02}:{:02}",
+      total_seconds / 3600,
+      (total_seconds / 60) % 60,
+      total_seconds % 60,
+      frame % timebase
+   )
+}
+
+/// Marker fields are pipe separated and a line each, so both are removed
+fn marker_name(text: &str) -> String {
+   super::single_line(&text.replace('|', "/"))
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use super::{
+   escape_xml, file_url, marker_frames, marker_kind, single_line, tag_list,
+   ExportContext, ExportOutcome, MarkerKind, NoteExporter,
+};
+use crate::obs_websocket_connection::models::FrameRate;
+
+pub struct FcpxmlExporter;
+
+impl NoteExporter for FcpxmlExporter {
+   fn id(&self) -> &'static str {
+      "fcpxml"
+   }
+
+   fn name(&self) -> &'static str {
+      "Final Cut Pro XML"
+   }
+
+   fn description(&self) -> &'static str {
+      "A project with the recording and a marker per note (.fcpxml)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      super::write_sidecar(context.output_path, "fcpxml", &render(context))
+   }
+}
+
+/// A project holding the recording as a single clip with a marker per note
+fn render(context: &ExportContext) -> String {
+   let frame_rate = context.frame_rate;
+   let name = escape_xml(&context.recording_name());
+   let duration = rational_time(context.duration_frames(), frame_rate);
+   let frame_duration = rational_time(1, frame_rate);
+
+   let mut xml = String::new();
+   let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+   let _ = writeln!(xml, "<!DOCTYPE fcpxml>");
+   let _ = writeln!(xml, r#"<fcpxml version="1.9">"#);
+   let _ = writeln!(xml, "  <resources>");
+   let _ = writeln!(
+      xml,
+      r#"    <format id="r1" frameDuration="{}"/>"#,
+      frame_duration
+   );
+   let _ = writeln!(
+      xml,
+      r#"    <asset id="r2" name="{}" start="0s" duration="{}" hasVideo="1" hasAudio="1" format="r1">"#,
+      name, duration
+   );
+   let _ = writeln!(
+      xml,
+      r#"      <media-rep kind="original-media" src="{}"/>"#,
+      escape_xml(&file_url(context.output_path))
+   );
+   let _ = writeln!(xml, "    </asset>");
+   let _ = writeln!(xml, "  </resources>");
+   let _ = writeln!(xml, "  <library>");
+   let _ = writeln!(xml, r#"    <event name="Clip Mark">"#);
+   let _ = writeln!(xml, r#"      <project name="{}">"#, name);
+   let _ = writeln!(
+      xml,
+      r#"        <sequence format="r1" duration="{}" tcStart="0s" tcFormat="NDF">"#,
+      duration
+   );
+   let _ = writeln!(xml, "          <spine>");
+   let _ = writeln!(
+      xml,
+      r#"            <asset-clip ref="r2" name="{}" offset="0s" start="0s" duration="{}" tcFormat="NDF">"#,
+      name, duration
+   );
+
+   for note in context.notes {
+      let (kind, text) = marker_kind(note);
+      let start =
+         rational_time(frame_rate.frames_from_ms(note.timecode_ms), frame_rate);
+      let value = escape_xml(&single_line(text));
+      let marker_duration =
+         rational_time(marker_frames(note, frame_rate), frame_rate);
+      let tags = match tag_list(note) {
+         tags if tags.is_empty() => String::new(),
+         tags => format!(r#" note="{}""#, escape_xml(&tags)),
+      };
+
+      let _ = match kind {
+         MarkerKind::Standard => writeln!(
+            xml,
+            r#"              <marker start="{}" duration="{}" value="{}"{}/>"#,
+            start, marker_duration, value, tags
+         ),
+         MarkerKind::ToDo => writeln!(
+            xml,
+            r#"              <marker start="{}" duration="{}" value="{}"{} completed="0"/>"#,
+            start, marker_duration, value, tags
+         ),
+         MarkerKind::Chapter => writeln!(
+            xml,
+            r#"              <chapter-marker start="{}" duration="{}" value="{}" posterOffset="0s"/>"#,
+            start, marker_duration, value
+         ),
+      };
+   }
+
+   let _ = writeln!(xml, "            </asset-clip>");
+   let _ = writeln!(xml, "          </spine>");
+   let _ = writeln!(xml, "        </sequence>");
+   let _ = writeln!(xml, "      </project>");
+   let _ = writeln!(xml, "    </event>");
+   let _ = writeln!(xml, "  </library>");
+   let _ = writeln!(xml, "</fcpxml>");
+
+   xml
+}
+
+/// FCPXML times are rational seconds, e.g. `1001/30000s` for one frame
+fn rational_time(frames: i64, frame_rate: FrameRate) -> String {
+   if frames == 0 {
+      return "0s".to_string();
+   }
+
+   format!(
+      "{}/{}s",
+      frames * frame_rate.denominator as i64,
+      frame_rate.numerator
+   )
+}
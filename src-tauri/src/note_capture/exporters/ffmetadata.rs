@@ -0,0 +1,137 @@
+use std::{
+   fmt::Write,
+   path::{Path, PathBuf},
+   process::Command,
+};
+
+use super::{ExportContext, ExportOutcome, NoteExporter, FALLBACK_CHAPTER_MS};
+
+pub const ID: &str = "ffmetadata";
+
+const EXTENSION: &str = "ffmetadata";
+
+pub struct FfmetadataExporter;
+
+impl NoteExporter for FfmetadataExporter {
+   fn id(&self) -> &'static str {
+      ID
+   }
+
+   fn name(&self) -> &'static str {
+      "FFmpeg chapters"
+   }
+
+   fn description(&self) -> &'static str {
+      "FFmpeg chapter metadata, embedded in the recording when FFmpeg is set (.ffmetadata)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let mut metadata = String::from(";FFMETADATA1\n");
+      let _ = writeln!(metadata, "title={}", escape(&context.recording_name()));
+
+      for chapter in super::chapters(
+         context.notes,
+         context.recording_duration_ms,
+         FALLBACK_CHAPTER_MS,
+      ) {
+         let _ = writeln!(metadata);
+         let _ = writeln!(metadata, "[CHAPTER]");
+         let _ = writeln!(metadata, "TIMEBASE=1/1000");
+         let _ = writeln!(metadata, "START={}", chapter.start_ms);
+         let _ = writeln!(metadata, "END={}", chapter.end_ms);
+         let _ = writeln!(metadata, "title={}", escape(&chapter.title));
+      }
+
+      super::write_sidecar(context.output_path, EXTENSION, &metadata)
+   }
+}
+
+fn metadata_path(output_path: &Path) -> PathBuf {
+   output_path.with_extension(EXTENSION)
+}
+
+/// Remux the recording with the chapters from its `.ffmetadata` sidecar,
+/// keeping the recording's own container metadata.
+///
+/// FFmpeg writes to a temporary file next to the recording, which only
+/// replaces the original once FFmpeg has succeeded.
+pub fn embed_chapters(
+   ffmpeg_path: &Path,
+   output_path: &Path,
+) -> Result<(), std::io::Error> {
+   let metadata_path = metadata_path(output_path);
+   let extension = output_path
+      .extension()
+      .map(|ext| ext.to_string_lossy().to_string())
+      .unwrap_or_default();
+   let remuxed_path =
+      output_path.with_extension(format!("chapters-tmp.{}", extension));
+
+   let mut command = Command::new(ffmpeg_path);
+   command
+      .args(["-y", "-loglevel", "error", "-i"])
+      .arg(output_path)
+      .arg("-i")
+      .arg(&metadata_path)
+      .args([
+         "-map",
+         "0",
+         "-map_metadata",
+         "0",
+         "-map_chapters",
+         "1",
+         "-codec",
+         "copy",
+      ])
+      .arg(&remuxed_path);
+
+   #[cfg(windows)]
+   {
+      use std::os::windows::process::CommandExt;
+      const CREATE_NO_WINDOW: u32 = 0x08000000;
+      command.creation_flags(CREATE_NO_WINDOW);
+   }
+
+   let output = command.output();
+   let remuxed = match output {
+      Ok(output) if output.status.success() => std::fs::metadata(&remuxed_path)
+         .map(|metadata| metadata.len() > 0)
+         .unwrap_or(false),
+      Ok(output) => {
+         let _ = std::fs::remove_file(&remuxed_path);
+         return Err(std::io::Error::other(format!(
+            "FFmpeg exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+         )));
+      }
+      Err(err) => {
+         let _ = std::fs::remove_file(&remuxed_path);
+         return Err(err);
+      }
+   };
+
+   if !remuxed {
+      let _ = std::fs::remove_file(&remuxed_path);
+      return Err(std::io::Error::other("FFmpeg produced an empty file"));
+   }
+
+   std::fs::rename(&remuxed_path, output_path)
+}
+
+/// Special characters are backslash escaped in FFMETADATA values
+fn escape(text: &str) -> String {
+   let mut escaped = String::with_capacity(text.len());
+   for c in text.chars() {
+      if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+         escaped.push('\\');
+      }
+      escaped.push(c);
+   }
+
+   escaped
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{ExportContext, ExportOutcome, NoteExporter};
+use crate::note_capture::models::Note;
+
+/// Bump when a field is removed or changes meaning, new fields are additive
+const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotesDocument<'a> {
+   schema_version: u32,
+   recording: RecordingDetails<'a>,
+   notes: &'a [Note],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingDetails<'a> {
+   path: String,
+   obs_version: Option<&'a str>,
+   started_at: Option<DateTime<Utc>>,
+   duration_ms: Option<i64>,
+   pauses: Vec<PauseDetails>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PauseDetails {
+   /// Position in the recording the pause happened at
+   timecode_ms: Option<i64>,
+   started_at: Option<DateTime<Utc>>,
+   duration_ms: i64,
+}
+
+pub struct JsonExporter;
+
+impl NoteExporter for JsonExporter {
+   fn id(&self) -> &'static str {
+      "json"
+   }
+
+   fn name(&self) -> &'static str {
+      "JSON"
+   }
+
+   fn description(&self) -> &'static str {
+      "Notes with recording details for other tools to read (.notes.json)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let document = NotesDocument {
+         schema_version: SCHEMA_VERSION,
+         recording: RecordingDetails {
+            path: context.output_path.to_string_lossy().to_string(),
+            obs_version: context.obs_version,
+            started_at: context
+               .recording_start
+               .and_then(DateTime::from_timestamp_millis),
+            duration_ms: context.recording_duration_ms,
+            pauses: pause_details(context),
+         },
+         notes: context.notes,
+      };
+
+      let contents = serde_json::to_string_pretty(&document)
+         .map_err(std::io::Error::other)?;
+      super::write_sidecar(context.output_path, "notes.json", &contents)
+   }
+}
+
+fn pause_details(context: &ExportContext) -> Vec<PauseDetails> {
+   let mut paused_so_far = 0;
+
+   context
+      .pauses
+      .iter()
+      .map(|pause| {
+         let timecode_ms = context
+            .recording_start
+            .map(|start| pause.start - start - paused_so_far);
+         let duration_ms = pause.end - pause.start;
+         paused_so_far += duration_ms;
+
+         PauseDetails {
+            timecode_ms,
+            started_at: DateTime::from_timestamp_millis(pause.start),
+            duration_ms,
+         }
+      })
+      .collect()
+}
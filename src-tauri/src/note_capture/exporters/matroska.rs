@@ -0,0 +1,78 @@
+use std::fmt::Write;
+
+use super::{
+   escape_xml, ExportContext, ExportOutcome, NoteExporter, FALLBACK_CHAPTER_MS,
+};
+
+/// Matroska chapters XML, as accepted by mkvmerge and MKVToolNix
+pub struct MatroskaChaptersExporter;
+
+impl NoteExporter for MatroskaChaptersExporter {
+   fn id(&self) -> &'static str {
+      "matroska_chapters"
+   }
+
+   fn name(&self) -> &'static str {
+      "Matroska chapters"
+   }
+
+   fn description(&self) -> &'static str {
+      "Chapters for mkvmerge and MKVToolNix (.chapters.xml)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let mut xml = String::new();
+      let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+      let _ =
+         writeln!(xml, r#"<!DOCTYPE Chapters SYSTEM "matroskachapters.dtd">"#);
+      let _ = writeln!(xml, "<Chapters>");
+      let _ = writeln!(xml, "  <EditionEntry>");
+
+      for chapter in super::chapters(
+         context.notes,
+         context.recording_duration_ms,
+         FALLBACK_CHAPTER_MS,
+      ) {
+         let _ = writeln!(xml, "    <ChapterAtom>");
+         let _ = writeln!(
+            xml,
+            "      <ChapterTimeStart>{}</ChapterTimeStart>",
+            format_timestamp(chapter.start_ms)
+         );
+         let _ = writeln!(
+            xml,
+            "      <ChapterTimeEnd>{}</ChapterTimeEnd>",
+            format_timestamp(chapter.end_ms)
+         );
+         let _ = writeln!(xml, "      <ChapterDisplay>");
+         let _ = writeln!(
+            xml,
+            "        <ChapterString>{}</ChapterString>",
+            escape_xml(&chapter.title)
+         );
+         let _ =
+            writeln!(xml, "        <ChapterLanguage>und</ChapterLanguage>");
+         let _ = writeln!(xml, "      </ChapterDisplay>");
+         let _ = writeln!(xml, "    </ChapterAtom>");
+      }
+
+      let _ = writeln!(xml, "  </EditionEntry>");
+      let _ = writeln!(xml, "</Chapters>");
+
+      super::write_sidecar(context.output_path, "chapters.xml", &xml)
+   }
+}
+
+/// `HH:MM:SS.nnnnnnnnn`, Matroska timestamps are in nanoseconds
+fn format_timestamp(timecode_ms: i64) -> String {
+   let (hours, minutes, seconds, milliseconds) =
+      super::split_timecode(timecode_ms);
+   format!(
+      "{:02}:{:02}:{:02}.{:03}000000",
+      hours, minutes, seconds, milliseconds
+   )
+}
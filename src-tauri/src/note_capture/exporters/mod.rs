@@ -0,0 +1,468 @@
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use tauri::Manager;
+
+use crate::{
+   note_capture::models::{Note, TagStyles},
+   obs_websocket_connection::models::{FrameRate, Pause},
+   state::AppSettingsState,
+   GlobalState,
+};
+
+mod csv;
+mod edl;
+mod fcpxml;
+mod ffmetadata;
+mod json;
+mod matroska;
+mod otio;
+mod report;
+mod srt;
+mod text;
+mod vtt;
+mod xmeml;
+mod youtube;
+
+pub use ffmetadata::{embed_chapters, ID as FFMETADATA_ID};
+pub use text::ID as TEXT_ID;
+
+/// Writes the notes of a finished recording in one output format
+pub trait NoteExporter: Send + Sync {
+   /// Stable identifier saved in the app settings
+   fn id(&self) -> &'static str;
+
+   fn name(&self) -> &'static str;
+
+   fn description(&self) -> &'static str;
+
+   /// Whether the output holds each note in full, so it can stand in for
+   /// the note file
+   fn writes_every_note(&self) -> bool {
+      true
+   }
+
+   /// Problems the user should know about, that did not stop the export,
+   /// are pushed to `warnings`
+   fn export(
+      &self,
+      context: &ExportContext,
+      warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error>;
+}
+
+/// Whether an exporter left a file behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportOutcome {
+   Written,
+   /// Nothing worth writing, the reason is given as a warning
+   Skipped,
+}
+
+/// Every available exporter, in the order they run
+static EXPORTERS: &[&dyn NoteExporter] = &[
+   &text::TextExporter,
+   &srt::SrtExporter,
+   &vtt::VttExporter,
+   &vtt::VttChaptersExporter,
+   &json::JsonExporter,
+   &csv::CsvExporter,
+   &edl::EdlExporter,
+   &fcpxml::FcpxmlExporter,
+   &xmeml::PremiereExporter,
+   &youtube::YoutubeChaptersExporter,
+   &ffmetadata::FfmetadataExporter,
+   &matroska::MatroskaChaptersExporter,
+   &otio::OtioExporter,
+   &report::MarkdownReportExporter,
+   &report::HtmlReportExporter,
+];
+
+pub fn exporters() -> &'static [&'static dyn NoteExporter] {
+   EXPORTERS
+}
+
+pub fn find_exporter(id: &str) -> Option<&'static dyn NoteExporter> {
+   EXPORTERS
+      .iter()
+      .copied()
+      .find(|exporter| exporter.id() == id)
+}
+
+pub struct ExportSummary {
+   /// Warnings and failures worth showing to the user
+   pub warnings: Vec<String>,
+   pub failed: usize,
+   /// Ids of the exporters that wrote their output
+   pub written: Vec<&'static str>,
+   /// One of the written outputs holds every note in full
+   pub has_every_note: bool,
+}
+
+/// Everything an exporter needs to write a sidecar for a finished recording
+pub struct ExportContext<'a> {
+   pub output_path: &'a Path,
+   /// Note file written to while recording
+   pub note_file_path: &'a Path,
+   pub notes: &'a [Note],
+   pub settings: &'a AppSettingsState,
+   /// Recording length excluding paused time, when known
+   pub recording_duration_ms: Option<i64>,
+   /// Wall-clock milliseconds the recording started at
+   pub recording_start: Option<i64>,
+   pub pauses: &'a [Pause],
+   /// Total time spent paused
+   pub paused_duration_ms: i64,
+   pub obs_version: Option<&'a str>,
+   /// OBS output frame rate, 30 fps when it could not be queried
+   pub frame_rate: FrameRate,
+}
+
+impl<'a> ExportContext<'a> {
+   /// Context for notes read back from a note file, which keeps neither
+   /// the pauses nor the length of the recording
+   pub fn for_note_file(
+      app_handle: &tauri::AppHandle,
+      output_path: &'a Path,
+      note_file_path: &'a Path,
+      notes: &'a [Note],
+      settings: &'a AppSettingsState,
+   ) -> Self {
+      Self {
+         output_path,
+         note_file_path,
+         notes,
+         settings,
+         recording_duration_ms: None,
+         recording_start: None,
+         pauses: &[],
+         paused_duration_ms: 0,
+         obs_version: None,
+         frame_rate: current_frame_rate(app_handle),
+      }
+   }
+
+   /// Recording file name without its extension
+   fn recording_name(&self) -> String {
+      self
+         .output_path
+         .file_stem()
+         .map(|stem| stem.to_string_lossy().to_string())
+         .unwrap_or_else(|| "Clip Mark".to_string())
+   }
+
+   /// Length of the recording in frames, falling back to a second past the
+   /// last note
+   fn duration_frames(&self) -> i64 {
+      let duration_ms = self.recording_duration_ms.unwrap_or_else(|| {
+         self
+            .notes
+            .iter()
+            .map(|note| note.timecode_ms + 1000)
+            .max()
+            .unwrap_or(1000)
+      });
+
+      self.frame_rate.frames_from_ms(duration_ms).max(1)
+   }
+}
+
+/// Extensions OBS can record to, used to find the recording a note file
+/// belongs to
+pub const RECORDING_EXTENSIONS: &[&str] = &["mkv", "mp4", "mov", "flv", "ts"];
+
+/// Recording sharing the note file's name, if there is one
+pub fn sibling_recording(note_file_path: &Path) -> Option<PathBuf> {
+   RECORDING_EXTENSIONS
+      .iter()
+      .map(|ext| note_file_path.with_extension(ext))
+      .find(|path| path.is_file())
+}
+
+/// OBS output frame rate as last queried, 30 fps until it has been
+pub fn current_frame_rate(app_handle: &tauri::AppHandle) -> FrameRate {
+   app_handle
+      .state::<GlobalState>()
+      .frame_rate
+      .lock()
+      .ok()
+      .and_then(|frame_rate| *frame_rate)
+      .unwrap_or_default()
+}
+
+/// Run the enabled exporters, in registry order
+pub fn export_notes(
+   context: &ExportContext,
+   enabled_exporters: &[String],
+) -> ExportSummary {
+   let mut summary = ExportSummary {
+      warnings: Vec::new(),
+      failed: 0,
+      written: Vec::new(),
+      has_every_note: false,
+   };
+
+   for exporter in EXPORTERS
+      .iter()
+      .filter(|exporter| enabled_exporters.iter().any(|id| id == exporter.id()))
+   {
+      match exporter.export(context, &mut summary.warnings) {
+         Ok(ExportOutcome::Written) => {
+            summary.written.push(exporter.id());
+            summary.has_every_note |= exporter.writes_every_note();
+         }
+         Ok(ExportOutcome::Skipped) => {}
+         Err(err) => {
+            warn!("Failed to export {} notes: {}", exporter.id(), err);
+            summary.warnings.push(format!(
+               "Failed to export {}: {}",
+               exporter.name(),
+               err
+            ));
+            summary.failed += 1;
+         }
+      }
+   }
+
+   summary
+}
+
+/// Write `contents` next to the recording, swapping its extension
+fn write_sidecar(
+   output_path: &Path,
+   extension: &str,
+   contents: &str,
+) -> Result<ExportOutcome, std::io::Error> {
+   std::fs::write(output_path.with_extension(extension), contents)?;
+
+   Ok(ExportOutcome::Written)
+}
+
+/// Format as `HH:MM:SS.mmm`, matching the note file
+pub fn format_timecode(timecode_ms: i64) -> String {
+   let (hours, minutes, seconds, milliseconds) = split_timecode(timecode_ms);
+   format!(
+      "{:02}:{:02}:{:02}.{:03}",
+      hours, minutes, seconds, milliseconds
+   )
+}
+
+/// Split milliseconds into hours, minutes, seconds and milliseconds
+fn split_timecode(timecode_ms: i64) -> (i64, i64, i64, i64) {
+   let timecode_ms = timecode_ms.max(0);
+
+   (
+      timecode_ms / 3_600_000,
+      (timecode_ms / 60_000) % 60,
+      (timecode_ms / 1000) % 60,
+      timecode_ms % 1000,
+   )
+}
+
+/// End of a cue shown for `cue_duration_ms`, cut short so it does not
+/// overlap the next note
+fn cue_end(notes: &[Note], index: usize, cue_duration_ms: i64) -> i64 {
+   let start = notes[index].timecode_ms.max(0);
+
+   // Range notes already say how long they last
+   if let Some(end) = notes[index].end_timecode_ms {
+      return end.max(start);
+   }
+
+   let end = start + cue_duration_ms;
+
+   match notes.get(index + 1) {
+      Some(next) if next.timecode_ms > start => end.min(next.timecode_ms),
+      _ => end,
+   }
+}
+
+/// Length of a note marker in frames, a single frame unless the note marks
+/// a range
+fn marker_frames(note: &Note, frame_rate: FrameRate) -> i64 {
+   note
+      .end_timecode_ms
+      .map(|end| {
+         frame_rate.frames_from_ms(end)
+            - frame_rate.frames_from_ms(note.timecode_ms)
+      })
+      .unwrap_or(1)
+      .max(1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MarkerKind {
+   Standard,
+   Chapter,
+   ToDo,
+}
+
+const MARKER_KINDS: [([&str; 2], &str, MarkerKind); 2] = [
+   (["chapter:", "#chapter"], "chapter", MarkerKind::Chapter),
+   (["todo:", "#todo"], "todo", MarkerKind::ToDo),
+];
+
+/// Pick the marker kind from a `chapter:`/`todo:` or `#chapter`/`#todo`
+/// prefix, returning the note text without it, or else from a `chapter` or
+/// `todo` tag anywhere in the note
+fn marker_kind(note: &Note) -> (MarkerKind, &str) {
+   let text = note.text.as_str();
+   let trimmed = text.trim_start();
+
+   for (prefixes, _, kind) in MARKER_KINDS {
+      for prefix in prefixes {
+         let Some(rest) = trimmed
+            .get(..prefix.len())
+            .filter(|start| start.eq_ignore_ascii_case(prefix))
+            .map(|_| &trimmed[prefix.len()..])
+         else {
+            continue;
+         };
+
+         // `#chapter` must be a whole word, `#chapters` is not a prefix
+         if prefix.ends_with(':')
+            || rest.is_empty()
+            || rest.starts_with(char::is_whitespace)
+         {
+            return (kind, rest.trim_start());
+         }
+      }
+   }
+
+   for (_, tag, kind) in MARKER_KINDS {
+      if note.tags.iter().any(|name| name.eq_ignore_ascii_case(tag)) {
+         return (kind, text);
+      }
+   }
+
+   (MarkerKind::Standard, text)
+}
+
+/// Chapters without a following note or known recording end last this long
+const FALLBACK_CHAPTER_MS: i64 = 1000;
+
+struct Chapter {
+   start_ms: i64,
+   end_ms: i64,
+   title: String,
+}
+
+/// Each note starts a chapter that runs until the next note, the last one
+/// ending with the recording or after `fallback_duration_ms`
+fn chapters(
+   notes: &[Note],
+   recording_duration_ms: Option<i64>,
+   fallback_duration_ms: i64,
+) -> Vec<Chapter> {
+   let mut chapters = Vec::new();
+   let mut index = 0;
+
+   while index < notes.len() {
+      let start_ms = notes[index].timecode_ms.max(0);
+
+      // Notes taken while paused share a timecode, so they share a chapter
+      let mut titles = Vec::new();
+      while index < notes.len() && notes[index].timecode_ms.max(0) == start_ms {
+         titles.push(single_line(&notes[index].text));
+         index += 1;
+      }
+
+      let end_ms = match notes.get(index) {
+         Some(next) => next.timecode_ms,
+         None => recording_duration_ms
+            .filter(|duration| *duration > start_ms)
+            .unwrap_or(start_ms + fallback_duration_ms),
+      };
+
+      chapters.push(Chapter {
+         start_ms,
+         end_ms,
+         title: titles.join(" / "),
+      });
+   }
+
+   chapters
+}
+
+/// The palette entry closest to the color of the first styled tag on the
+/// note, for formats limited to a fixed set of marker colors
+fn tag_color<'a>(
+   note: &Note,
+   tag_styles: &TagStyles,
+   palette: &[(&'a str, [u8; 3])],
+) -> Option<&'a str> {
+   let rgb = note
+      .tags
+      .iter()
+      .filter_map(|tag| tag_styles.get(tag))
+      .find_map(|style| parse_hex_color(&style.color))?;
+
+   palette
+      .iter()
+      .min_by_key(|(_, color)| {
+         color
+            .iter()
+            .zip(rgb)
+            .map(|(a, b)| (*a as i32 - b as i32).pow(2))
+            .sum::<i32>()
+      })
+      .map(|(name, _)| *name)
+}
+
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+   let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+   let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+   Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// `#tag #tag` for formats with a free-form comment next to the title
+pub fn tag_list(note: &Note) -> String {
+   note
+      .tags
+      .iter()
+      .map(|tag| format!("#{}", tag))
+      .collect::<Vec<_>>()
+      .join(" ")
+}
+
+/// Collapse a note onto one line for formats with single-line titles
+fn single_line(text: &str) -> String {
+   text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn escape_xml(text: &str) -> String {
+   text
+      .replace('&', "&amp;")
+      .replace('<', "&lt;")
+      .replace('>', "&gt;")
+      .replace('"', "&quot;")
+      .replace('\'', "&apos;")
+}
+
+/// `file://` URL for a local path, Windows drive paths included
+pub fn file_url(path: &Path) -> String {
+   let path = path.to_string_lossy().replace('\\', "/");
+   let mut url = String::from(if path.starts_with('/') {
+      "file://"
+   } else {
+      "file:///"
+   });
+
+   for byte in path.bytes() {
+      match byte {
+         b'A'..=b'Z'
+         | b'a'..=b'z'
+         | b'0'..=b'9'
+         | b'-'
+         | b'.'
+         | b'_'
+         | b'~'
+         | b'/'
+         | b':' => url.push(byte as char),
+         _ => url.push_str(&format!("%{:02X}", byte)),
+      }
+   }
+
+   url
+}
@@ -0,0 +1,145 @@
+use serde_json::{json, Value};
+
+use super::{file_url, tag_color, ExportContext, ExportOutcome, NoteExporter};
+use crate::obs_websocket_connection::models::FrameRate;
+
+/// OTIO marker colors, matched against tag colors
+const MARKER_COLORS: &[(&str, [u8; 3])] = &[
+   ("PINK", [0xff, 0x69, 0xb4]),
+   ("RED", [0xe6, 0x1e, 0x1e]),
+   ("ORANGE", [0xff, 0x8c, 0x00]),
+   ("YELLOW", [0xf0, 0xdd, 0x00]),
+   ("GREEN", [0x00, 0xa8, 0x00]),
+   ("CYAN", [0x00, 0xce, 0xd1]),
+   ("BLUE", [0x2f, 0x80, 0xed]),
+   ("PURPLE", [0x80, 0x00, 0xc8]),
+   ("MAGENTA", [0xff, 0x00, 0xff]),
+   ("BLACK", [0x00, 0x00, 0x00]),
+   ("WHITE", [0xff, 0xff, 0xff]),
+];
+
+/// A timeline with one clip referencing the recording, notes as markers
+pub struct OtioExporter;
+
+impl NoteExporter for OtioExporter {
+   fn id(&self) -> &'static str {
+      "otio"
+   }
+
+   fn name(&self) -> &'static str {
+      "OpenTimelineIO"
+   }
+
+   fn description(&self) -> &'static str {
+      "A timeline with the recording and a marker per note (.otio)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let frame_rate = context.frame_rate;
+      let name = context.recording_name();
+      let clip_range = time_range(0, context.duration_frames(), frame_rate);
+
+      let markers = context
+         .notes
+         .iter()
+         .map(|note| {
+            let start = frame_rate.frames_from_ms(note.timecode_ms);
+            let duration = note
+               .end_timecode_ms
+               .map(|end| frame_rate.frames_from_ms(end) - start)
+               .unwrap_or(0)
+               .max(0);
+
+            json!({
+               "OTIO_SCHEMA": "Marker.2",
+               "name": super::single_line(&note.text),
+               "color": tag_color(
+                  note,
+                  &context.settings.tag_styles,
+                  MARKER_COLORS,
+               )
+               .unwrap_or("RED"),
+               "comment": note.text,
+               "marked_range": time_range(start, duration, frame_rate),
+               "metadata": {
+                  "clip_mark": {
+                     "id": note.id,
+                     "created_at": note.created_at,
+                     "tags": note.tags,
+                  },
+               },
+            })
+         })
+         .collect::<Vec<_>>();
+
+      let timeline = json!({
+         "OTIO_SCHEMA": "Timeline.1",
+         "name": name,
+         "global_start_time": null,
+         "metadata": {},
+         "tracks": {
+            "OTIO_SCHEMA": "Stack.1",
+            "name": "tracks",
+            "source_range": null,
+            "effects": [],
+            "markers": [],
+            "enabled": true,
+            "metadata": {},
+            "children": [{
+               "OTIO_SCHEMA": "Track.1",
+               "name": "Video 1",
+               "kind": "Video",
+               "source_range": null,
+               "effects": [],
+               "markers": [],
+               "enabled": true,
+               "metadata": {},
+               "children": [{
+                  "OTIO_SCHEMA": "Clip.2",
+                  "name": name,
+                  "source_range": clip_range,
+                  "media_references": {
+                     "DEFAULT_MEDIA": {
+                        "OTIO_SCHEMA": "ExternalReference.1",
+                        "name": name,
+                        "target_url": file_url(context.output_path),
+                        "available_range": clip_range,
+                        "available_image_bounds": null,
+                        "metadata": {},
+                     },
+                  },
+                  "active_media_reference_key": "DEFAULT_MEDIA",
+                  "effects": [],
+                  "markers": markers,
+                  "enabled": true,
+                  "metadata": {},
+               }],
+            }],
+         },
+      });
+
+      let contents = serde_json::to_string_pretty(&timeline)
+         .map_err(std::io::Error::other)?;
+      super::write_sidecar(context.output_path, "otio", &contents)
+   }
+}
+
+fn time_range(start: i64, duration: i64, frame_rate: FrameRate) -> Value {
+   json!({
+      "OTIO_SCHEMA": "TimeRange.1",
+      "start_time": rational_time(start, frame_rate),
+      "duration": rational_time(duration, frame_rate),
+   })
+}
+
+fn rational_time(frames: i64, frame_rate: FrameRate) -> Value {
+   json!({
+      "OTIO_SCHEMA": "RationalTime.1",
+      "rate": frame_rate.fps(),
+      "value": frames as f64,
+   })
+}
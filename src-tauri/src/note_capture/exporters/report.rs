@@ -0,0 +1,420 @@
+use std::fmt::Write;
+
+use chrono::DateTime;
+
+use super::{
+   escape_xml, format_timecode, single_line, ExportContext, ExportOutcome,
+   NoteExporter,
+};
+use crate::note_capture::{models::Note, templates::format_wallclock};
+
+/// Session summary to share with the team, as Markdown
+pub struct MarkdownReportExporter;
+
+impl NoteExporter for MarkdownReportExporter {
+   fn id(&self) -> &'static str {
+      "report_markdown"
+   }
+
+   fn name(&self) -> &'static str {
+      "Session report (Markdown)"
+   }
+
+   fn description(&self) -> &'static str {
+      "Durations, notes grouped by tag and gaps between notes (.report.md)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let report = Report::new(context);
+      super::write_sidecar(
+         context.output_path,
+         "report.md",
+         &render_markdown(&report),
+      )
+   }
+}
+
+/// The same session summary as a standalone web page
+pub struct HtmlReportExporter;
+
+impl NoteExporter for HtmlReportExporter {
+   fn id(&self) -> &'static str {
+      "report_html"
+   }
+
+   fn name(&self) -> &'static str {
+      "Session report (HTML)"
+   }
+
+   fn description(&self) -> &'static str {
+      "Durations, notes grouped by tag and gaps between notes (.report.html)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let report = Report::new(context);
+      super::write_sidecar(
+         context.output_path,
+         "report.html",
+         &render_html(&report),
+      )
+   }
+}
+
+/// Notes carrying one tag, or no tag at all when `tag` is `None`
+struct TagGroup<'a> {
+   tag: Option<&'a str>,
+   description: Option<&'a str>,
+   color: Option<&'a str>,
+   notes: Vec<&'a Note>,
+}
+
+/// Stretch of the recording without any note
+struct Gap {
+   start_ms: i64,
+   end_ms: i64,
+}
+
+/// Everything the report shows, shared by both formats
+struct Report<'a> {
+   recording: String,
+   started_at: Option<String>,
+   obs_version: Option<&'a str>,
+   /// Recorded time, excluding pauses
+   recorded_ms: Option<i64>,
+   paused_ms: i64,
+   note_count: usize,
+   groups: Vec<TagGroup<'a>>,
+   gap_threshold_ms: i64,
+   gaps: Vec<Gap>,
+}
+
+impl<'a> Report<'a> {
+   fn new(context: &'a ExportContext) -> Self {
+      let recording = context
+         .output_path
+         .file_name()
+         .map(|name| name.to_string_lossy().to_string())
+         .unwrap_or_else(|| context.recording_name());
+      let gap_threshold_ms = context.settings.report_gap_threshold_ms as i64;
+
+      Self {
+         recording,
+         started_at: context
+            .recording_start
+            .and_then(DateTime::from_timestamp_millis)
+            .map(format_wallclock),
+         obs_version: context.obs_version,
+         recorded_ms: context.recording_duration_ms,
+         paused_ms: context.paused_duration_ms,
+         note_count: context.notes.len(),
+         groups: group_by_tag(context),
+         gap_threshold_ms,
+         gaps: gaps(
+            context.notes,
+            context.recording_duration_ms,
+            gap_threshold_ms,
+         ),
+      }
+   }
+}
+
+/// Groups in order of each tag's first use, untagged notes last. Notes with
+/// several tags show up under each of them.
+fn group_by_tag<'a>(context: &'a ExportContext) -> Vec<TagGroup<'a>> {
+   let mut groups: Vec<TagGroup> = Vec::new();
+   let mut untagged = Vec::new();
+
+   for note in context.notes {
+      if note.tags.is_empty() {
+         untagged.push(note);
+      }
+
+      for tag in &note.tags {
+         match groups.iter_mut().find(|group| group.tag == Some(tag)) {
+            Some(group) => group.notes.push(note),
+            None => {
+               let style = context.settings.tag_styles.get(tag);
+               groups.push(TagGroup {
+                  tag: Some(tag),
+                  description: style
+                     .map(|style| style.description.as_str())
+                     .filter(|description| !description.is_empty()),
+                  color: style.map(|style| style.color.as_str()),
+                  notes: vec![note],
+               });
+            }
+         }
+      }
+   }
+
+   if !untagged.is_empty() {
+      groups.push(TagGroup {
+         tag: None,
+         description: None,
+         color: None,
+         notes: untagged,
+      });
+   }
+
+   groups
+}
+
+/// Stretches longer than `threshold_ms` with no note, range notes counting
+/// as covering their whole range
+fn gaps(
+   notes: &[Note],
+   recording_duration_ms: Option<i64>,
+   threshold_ms: i64,
+) -> Vec<Gap> {
+   let mut sorted: Vec<&Note> = notes.iter().collect();
+   sorted.sort_by_key(|note| note.timecode_ms);
+
+   let mut gaps = Vec::new();
+   let mut covered_until = 0;
+
+   for note in sorted {
+      if note.timecode_ms - covered_until > threshold_ms {
+         gaps.push(Gap {
+            start_ms: covered_until,
+            end_ms: note.timecode_ms,
+         });
+      }
+
+      covered_until =
+         covered_until.max(note.end_timecode_ms.unwrap_or(note.timecode_ms));
+   }
+
+   if let Some(duration) = recording_duration_ms {
+      if duration - covered_until > threshold_ms {
+         gaps.push(Gap {
+            start_ms: covered_until,
+            end_ms: duration,
+         });
+      }
+   }
+
+   gaps
+}
+
+/// `1h 02m 03s`, leaving out leading units that are zero
+fn format_duration(duration_ms: i64) -> String {
+   let total_seconds = duration_ms.max(0) / 1000;
+   let (hours, minutes, seconds) = (
+      total_seconds / 3600,
+      (total_seconds / 60) % 60,
+      total_seconds % 60,
+   );
+
+   if hours > 0 {
+      format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+   } else if minutes > 0 {
+      format!("{}m {:02}s", minutes, seconds)
+   } else {
+      format!("{}s", seconds)
+   }
+}
+
+/// Label and value rows of the summary table
+fn summary_rows(report: &Report) -> Vec<(&'static str, String)> {
+   let mut rows = Vec::new();
+
+   if let Some(started_at) = &report.started_at {
+      rows.push(("Started", started_at.clone()));
+   }
+   rows.push((
+      "Recorded",
+      report
+         .recorded_ms
+         .map(format_duration)
+         .unwrap_or_else(|| "Unknown".to_string()),
+   ));
+   rows.push(("Paused", format_duration(report.paused_ms)));
+   if let Some(recorded) = report.recorded_ms {
+      rows.push(("Total", format_duration(recorded + report.paused_ms)));
+   }
+   rows.push(("Notes", report.note_count.to_string()));
+   if let Some(obs_version) = report.obs_version {
+      rows.push(("OBS", obs_version.to_string()));
+   }
+
+   rows
+}
+
+fn note_timecode(note: &Note) -> String {
+   match note.end_timecode_ms {
+      Some(end) => {
+         format!(
+            "{} - {}",
+            format_timecode(note.timecode_ms),
+            format_timecode(end)
+         )
+      }
+      None => format_timecode(note.timecode_ms),
+   }
+}
+
+fn render_markdown(report: &Report) -> String {
+   let mut md = String::new();
+   let _ = writeln!(md, "# Session report: {}", report.recording);
+   let _ = writeln!(md);
+   let _ = writeln!(md, "| | |");
+   let _ = writeln!(md, "| --- | --- |");
+   for (label, value) in summary_rows(report) {
+      let _ = writeln!(md, "| {} | {} |", label, value);
+   }
+
+   let _ = writeln!(md);
+   let _ = writeln!(md, "## Notes by tag");
+   if report.groups.is_empty() {
+      let _ = writeln!(md);
+      let _ = writeln!(md, "No notes.");
+   }
+   for group in &report.groups {
+      let _ = writeln!(md);
+      match (group.tag, group.description) {
+         (Some(tag), Some(description)) => {
+            let _ = writeln!(md, "### #{} ({})", tag, description);
+         }
+         (Some(tag), None) => {
+            let _ = writeln!(md, "### #{}", tag);
+         }
+         (None, _) => {
+            let _ = writeln!(md, "### Untagged");
+         }
+      }
+      let _ = writeln!(md);
+      for note in &group.notes {
+         let _ = writeln!(
+            md,
+            "- `{}` {}",
+            note_timecode(note),
+            single_line(&note.text)
+         );
+      }
+   }
+
+   let _ = writeln!(md);
+   let _ = writeln!(
+      md,
+      "## Gaps over {}",
+      format_duration(report.gap_threshold_ms)
+   );
+   let _ = writeln!(md);
+   if report.gaps.is_empty() {
+      let _ = writeln!(md, "None.");
+   }
+   for gap in &report.gaps {
+      let _ = writeln!(
+         md,
+         "- `{} - {}` {} without notes",
+         format_timecode(gap.start_ms),
+         format_timecode(gap.end_ms),
+         format_duration(gap.end_ms - gap.start_ms)
+      );
+   }
+
+   md
+}
+
+fn render_html(report: &Report) -> String {
+   let mut html = String::new();
+   let title = escape_xml(&format!("Session report: {}", report.recording));
+
+   let _ = writeln!(html, "<!DOCTYPE html>");
+   let _ = writeln!(html, r#"<html lang="en">"#);
+   let _ = writeln!(html, "<head>");
+   let _ = writeln!(html, r#"<meta charset="utf-8">"#);
+   let _ = writeln!(html, "<title>{}</title>", title);
+   let _ = writeln!(
+      html,
+      "<style>\
+      body{{font-family:system-ui,sans-serif;max-width:48rem;margin:2rem auto;padding:0 1rem;color:#222}}\
+      th{{text-align:left;padding-right:2rem}}\
+      code{{color:#555}}\
+      .swatch{{display:inline-block;width:.8em;height:.8em;border-radius:50%;margin-right:.4em}}\
+      </style>"
+   );
+   let _ = writeln!(html, "</head>");
+   let _ = writeln!(html, "<body>");
+   let _ = writeln!(html, "<h1>{}</h1>", title);
+
+   let _ = writeln!(html, "<table>");
+   for (label, value) in summary_rows(report) {
+      let _ = writeln!(
+         html,
+         "<tr><th>{}</th><td>{}</td></tr>",
+         label,
+         escape_xml(&value)
+      );
+   }
+   let _ = writeln!(html, "</table>");
+
+   let _ = writeln!(html, "<h2>Notes by tag</h2>");
+   if report.groups.is_empty() {
+      let _ = writeln!(html, "<p>No notes.</p>");
+   }
+   for group in &report.groups {
+      let swatch = group
+         .color
+         .map(|color| {
+            format!(
+               r#"<span class="swatch" style="background:{}"></span>"#,
+               escape_xml(color)
+            )
+         })
+         .unwrap_or_default();
+      let heading = match (group.tag, group.description) {
+         (Some(tag), Some(description)) => {
+            format!("#{} ({})", tag, description)
+         }
+         (Some(tag), None) => format!("#{}", tag),
+         (None, _) => "Untagged".to_string(),
+      };
+
+      let _ = writeln!(html, "<h3>{}{}</h3>", swatch, escape_xml(&heading));
+      let _ = writeln!(html, "<ul>");
+      for note in &group.notes {
+         let _ = writeln!(
+            html,
+            "<li><code>{}</code> {}</li>",
+            note_timecode(note),
+            escape_xml(&single_line(&note.text))
+         );
+      }
+      let _ = writeln!(html, "</ul>");
+   }
+
+   let _ = writeln!(
+      html,
+      "<h2>Gaps over {}</h2>",
+      format_duration(report.gap_threshold_ms)
+   );
+   if report.gaps.is_empty() {
+      let _ = writeln!(html, "<p>None.</p>");
+   } else {
+      let _ = writeln!(html, "<ul>");
+      for gap in &report.gaps {
+         let _ = writeln!(
+            html,
+            "<li><code>{} - {}</code> {} without notes</li>",
+            format_timecode(gap.start_ms),
+            format_timecode(gap.end_ms),
+            format_duration(gap.end_ms - gap.start_ms)
+         );
+      }
+      let _ = writeln!(html, "</ul>");
+   }
+
+   let _ = writeln!(html, "</body>");
+   let _ = writeln!(html, "</html>");
+
+   html
+}
@@ -0,0 +1,69 @@
+use std::fmt::Write;
+
+use super::{ExportContext, ExportOutcome, NoteExporter};
+use crate::note_capture::models::Note;
+
+pub struct SrtExporter;
+
+impl NoteExporter for SrtExporter {
+   fn id(&self) -> &'static str {
+      "srt"
+   }
+
+   fn name(&self) -> &'static str {
+      "SubRip subtitles"
+   }
+
+   fn description(&self) -> &'static str {
+      "A subtitle track with each note shown as a cue (.srt)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let contents =
+         render(context.notes, context.settings.cue_duration_ms as i64);
+      super::write_sidecar(context.output_path, "srt", &contents)
+   }
+}
+
+fn render(notes: &[Note], cue_duration_ms: i64) -> String {
+   let mut srt = String::new();
+
+   for (index, note) in notes.iter().enumerate() {
+      let start = note.timecode_ms.max(0);
+      let end = super::cue_end(notes, index, cue_duration_ms);
+
+      let _ = writeln!(srt, "{}", index + 1);
+      let _ = writeln!(
+         srt,
+         "{} --> {}",
+         format_timecode(start),
+         format_timecode(end)
+      );
+      let _ = writeln!(srt, "{}", cue_text(&note.text));
+      let _ = writeln!(srt);
+   }
+
+   srt
+}
+
+fn format_timecode(timecode_ms: i64) -> String {
+   let (hours, minutes, seconds, milliseconds) =
+      super::split_timecode(timecode_ms);
+   format!(
+      "{:02}:{:02}:{:02},{:03}",
+      hours, minutes, seconds, milliseconds
+   )
+}
+
+/// A blank line ends an SRT cue, so they are dropped from the note text
+fn cue_text(text: &str) -> String {
+   text
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .collect::<Vec<_>>()
+      .join("\n")
+}
@@ -0,0 +1,38 @@
+use super::{ExportContext, ExportOutcome, NoteExporter};
+
+pub const ID: &str = "txt";
+
+/// The note file written while recording, moved next to the recording
+pub struct TextExporter;
+
+impl NoteExporter for TextExporter {
+   fn id(&self) -> &'static str {
+      ID
+   }
+
+   fn name(&self) -> &'static str {
+      "Plain text"
+   }
+
+   fn description(&self) -> &'static str {
+      "The note file written while recording, a line per note (.txt)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let final_note_path = context.output_path.with_extension("txt");
+
+      if context.note_file_path != final_note_path {
+         if let Some(parent) = final_note_path.parent() {
+            std::fs::create_dir_all(parent)?;
+         }
+
+         std::fs::rename(context.note_file_path, &final_note_path)?;
+      }
+
+      Ok(ExportOutcome::Written)
+   }
+}
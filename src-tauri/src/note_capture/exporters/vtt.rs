@@ -0,0 +1,176 @@
+use std::fmt::Write;
+
+use super::{ExportContext, ExportOutcome, NoteExporter};
+use crate::note_capture::models::Note;
+
+pub struct VttExporter;
+
+impl NoteExporter for VttExporter {
+   fn id(&self) -> &'static str {
+      "vtt"
+   }
+
+   fn name(&self) -> &'static str {
+      "WebVTT subtitles"
+   }
+
+   fn description(&self) -> &'static str {
+      "A subtitle track for browser players with each note as a cue (.vtt)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let contents = render_captions(
+         context.notes,
+         context.settings.cue_duration_ms as i64,
+      );
+      super::write_sidecar(context.output_path, "vtt", &contents)
+   }
+}
+
+/// Each note starts a chapter that runs until the next note, the last one
+/// ending with the recording
+pub struct VttChaptersExporter;
+
+impl NoteExporter for VttChaptersExporter {
+   fn id(&self) -> &'static str {
+      "vtt_chapters"
+   }
+
+   fn name(&self) -> &'static str {
+      "WebVTT chapters"
+   }
+
+   fn description(&self) -> &'static str {
+      "A chapters track where each note starts a chapter (.chapters.vtt)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let contents = render_chapters(
+         context.notes,
+         context.recording_duration_ms,
+         context.settings.cue_duration_ms as i64,
+      );
+      super::write_sidecar(context.output_path, "chapters.vtt", &contents)
+   }
+}
+
+fn render_captions(notes: &[Note], cue_duration_ms: i64) -> String {
+   let mut vtt = String::from("WEBVTT\n\n");
+
+   for (index, note) in notes.iter().enumerate() {
+      write_cue(
+         &mut vtt,
+         index + 1,
+         note.timecode_ms.max(0),
+         super::cue_end(notes, index, cue_duration_ms),
+         &cue_text(&note.text),
+      );
+   }
+
+   vtt
+}
+
+fn render_chapters(
+   notes: &[Note],
+   recording_duration_ms: Option<i64>,
+   fallback_duration_ms: i64,
+) -> String {
+   let mut vtt = String::from("WEBVTT\n\n");
+
+   let chapters =
+      super::chapters(notes, recording_duration_ms, fallback_duration_ms);
+   for (index, chapter) in chapters.iter().enumerate() {
+      write_cue(
+         &mut vtt,
+         index + 1,
+         chapter.start_ms,
+         chapter.end_ms,
+         &escape(&chapter.title),
+      );
+   }
+
+   vtt
+}
+
+fn write_cue(
+   vtt: &mut String,
+   number: usize,
+   start: i64,
+   end: i64,
+   text: &str,
+) {
+   let _ = writeln!(vtt, "{}", number);
+   let _ = writeln!(
+      vtt,
+      "{} --> {}",
+      super::format_timecode(start),
+      super::format_timecode(end)
+   );
+   let _ = writeln!(vtt, "{}", text);
+   let _ = writeln!(vtt);
+}
+
+/// Escape markup and drop blank lines, which would end the cue early
+fn cue_text(text: &str) -> String {
+   text
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(escape)
+      .collect::<Vec<_>>()
+      .join("\n")
+}
+
+/// Escape markup. Escaping `>` also keeps `-->` out of the payload, where
+/// players would take it for the cue timings.
+fn escape(text: &str) -> String {
+   text
+      .replace('&', "&amp;")
+      .replace('<', "&lt;")
+      .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn note(id: u64, timecode_ms: i64, text: &str) -> Note {
+      Note {
+         id,
+         timecode_ms,
+         end_timecode_ms: None,
+         created_at: None,
+         scene: None,
+         text: text.to_string(),
+         tags: Vec::new(),
+      }
+   }
+
+   #[test]
+   fn escapes_cue_timing_arrow_in_captions() {
+      let vtt = render_captions(&[note(1, 1000, "a --> b")], 3000);
+
+      assert!(vtt.contains("\na --&gt; b\n"));
+      assert_eq!(vtt.matches("-->").count(), 1);
+   }
+
+   #[test]
+   fn escapes_cue_timing_arrow_in_chapters() {
+      let vtt = render_chapters(&[note(1, 0, "intro --> setup")], None, 3000);
+
+      assert!(vtt.contains("\nintro --&gt; setup\n"));
+      assert_eq!(vtt.matches("-->").count(), 1);
+   }
+
+   #[test]
+   fn drops_blank_lines_from_cues() {
+      assert_eq!(cue_text("first\n\n  \nsecond"), "first\nsecond");
+   }
+}
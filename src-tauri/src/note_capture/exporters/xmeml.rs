@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use super::{
+   escape_xml, file_url, single_line, tag_list, ExportContext, ExportOutcome,
+   NoteExporter,
+};
+use crate::obs_websocket_connection::models::FrameRate;
+
+/// Final Cut Pro 7 XML, which Premiere imports with clip and sequence markers
+pub struct PremiereExporter;
+
+impl NoteExporter for PremiereExporter {
+   fn id(&self) -> &'static str {
+      "premiere"
+   }
+
+   fn name(&self) -> &'static str {
+      "Premiere Pro XML"
+   }
+
+   fn description(&self) -> &'static str {
+      "A sequence with the recording and a marker per note (.premiere.xml)"
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      _warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      super::write_sidecar(
+         context.output_path,
+         "premiere.xml",
+         &render(context),
+      )
+   }
+}
+
+fn render(context: &ExportContext) -> String {
+   let frame_rate = context.frame_rate;
+   let name = escape_xml(&context.recording_name());
+   let file_name = escape_xml(
+      &context
+         .output_path
+         .file_name()
+         .map(|name| name.to_string_lossy().to_string())
+         .unwrap_or_default(),
+   );
+   let path_url = escape_xml(&file_url(context.output_path).replacen(
+      "file://",
+      "file://localhost",
+      1,
+   ));
+   let duration = context.duration_frames();
+   let rate = rate_element(frame_rate);
+   let markers = marker_elements(context);
+
+   let mut xml = String::new();
+   let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+   let _ = writeln!(xml, "<!DOCTYPE xmeml>");
+   let _ = writeln!(xml, r#"<xmeml version="4">"#);
+   let _ = writeln!(xml, r#"  <sequence id="sequence-1">"#);
+   let _ = writeln!(xml, "    <name>{}</name>", name);
+   let _ = writeln!(xml, "    <duration>{}</duration>", duration);
+   let _ = writeln!(xml, "    {}", rate);
+   let _ = writeln!(xml, "    <timecode>");
+   let _ = writeln!(xml, "      {}", rate);
+   let _ = writeln!(xml, "      <string>00:00:00:00</string>");
+   let _ = writeln!(xml, "      <frame>0</frame>");
+   let _ = writeln!(xml, "      <displayformat>NDF</displayformat>");
+   let _ = writeln!(xml, "    </timecode>");
+   let _ = writeln!(xml, "    <media>");
+   let _ = writeln!(xml, "      <video>");
+   let _ = writeln!(xml, "        <track>");
+   let _ = writeln!(xml, r#"          <clipitem id="clipitem-1">"#);
+   let _ = writeln!(xml, "            <name>{}</name>", name);
+   let _ = writeln!(xml, "            <duration>{}</duration>", duration);
+   let _ = writeln!(xml, "            {}", rate);
+   let _ = writeln!(xml, "            <start>0</start>");
+   let _ = writeln!(xml, "            <end>{}</end>", duration);
+   let _ = writeln!(xml, "            <in>0</in>");
+   let _ = writeln!(xml, "            <out>{}</out>", duration);
+   let _ = writeln!(xml, r#"            <file id="file-1">"#);
+   let _ = writeln!(xml, "              <name>{}</name>", file_name);
+   let _ = writeln!(xml, "              <pathurl>{}</pathurl>", path_url);
+   let _ = writeln!(xml, "              {}", rate);
+   let _ = writeln!(xml, "              <duration>{}</duration>", duration);
+   let _ = writeln!(xml, "              <media>");
+   let _ = writeln!(xml, "                <video/>");
+   let _ = writeln!(xml, "                <audio/>");
+   let _ = writeln!(xml, "              </media>");
+   let _ = writeln!(xml, "            </file>");
+   for marker in &markers {
+      let _ = writeln!(xml, "            {}", marker);
+   }
+   let _ = writeln!(xml, "          </clipitem>");
+   let _ = writeln!(xml, "        </track>");
+   let _ = writeln!(xml, "      </video>");
+   let _ = writeln!(xml, "    </media>");
+   for marker in &markers {
+      let _ = writeln!(xml, "    {}", marker);
+   }
+   let _ = writeln!(xml, "  </sequence>");
+   let _ = writeln!(xml, "</xmeml>");
+
+   xml
+}
+
+/// NTSC rates such as 29.97 are a whole timebase with the `ntsc` flag set
+fn rate_element(frame_rate: FrameRate) -> String {
+   format!(
+      "<rate><timebase>{}</timebase><ntsc>{}</ntsc></rate>",
+      frame_rate.timebase(),
+      if frame_rate.denominator == 1001 {
+         "TRUE"
+      } else {
+         "FALSE"
+      }
+   )
+}
+
+fn marker_elements(context: &ExportContext) -> Vec<String> {
+   context
+      .notes
+      .iter()
+      .map(|note| {
+         format!(
+            "<marker><name>{}</name><comment>{}</comment><in>{}</in><out>{}</out></marker>",
+            escape_xml(&single_line(&note.text)),
+            escape_xml(&tag_list(note)),
+            context.frame_rate.frames_from_ms(note.timecode_ms),
+            note
+               .end_timecode_ms
+               .map_or(-1, |end| context.frame_rate.frames_from_ms(end))
+         )
+      })
+      .collect()
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use super::{ExportContext, ExportOutcome, NoteExporter};
+use crate::note_capture::models::Note;
+
+/// YouTube ignores chapters shorter than this
+const MIN_CHAPTER_MS: i64 = 10_000;
+/// YouTube needs at least this many chapters to show any
+const MIN_CHAPTERS: usize = 3;
+
+struct Chapter {
+   start_ms: i64,
+   titles: Vec<String>,
+}
+
+/// Writes a block ready to paste into a video description, or only warns
+/// when the notes cannot form chapters YouTube would accept
+pub struct YoutubeChaptersExporter;
+
+impl NoteExporter for YoutubeChaptersExporter {
+   fn id(&self) -> &'static str {
+      "youtube_chapters"
+   }
+
+   fn name(&self) -> &'static str {
+      "YouTube chapters"
+   }
+
+   fn description(&self) -> &'static str {
+      "Chapters to paste into a video description (.chapters.txt)"
+   }
+
+   /// Notes closer than 10 seconds are merged into one chapter title
+   fn writes_every_note(&self) -> bool {
+      false
+   }
+
+   fn export(
+      &self,
+      context: &ExportContext,
+      warnings: &mut Vec<String>,
+   ) -> Result<ExportOutcome, std::io::Error> {
+      let (chapters, merged) =
+         build_chapters(context.notes, context.recording_duration_ms);
+
+      if merged > 0 {
+         warnings.push(format!(
+            "Merged {} note(s) into neighbouring YouTube chapters as chapters \
+          must be at least 10 seconds long",
+            merged
+         ));
+      }
+
+      if chapters.len() < MIN_CHAPTERS {
+         warnings.push(format!(
+         "YouTube chapters were not written, at least {} chapters 10 seconds \
+          apart are needed but only {} could be made",
+         MIN_CHAPTERS,
+         chapters.len()
+      ));
+         return Ok(ExportOutcome::Skipped);
+      }
+
+      super::write_sidecar(
+         context.output_path,
+         "chapters.txt",
+         &render(&chapters),
+      )
+   }
+}
+
+/// Chapters start at 0:00 and are at least 10 seconds long, shorter ones
+/// are merged into the chapter before them. Returns how many notes merged.
+fn build_chapters(
+   notes: &[Note],
+   recording_duration_ms: Option<i64>,
+) -> (Vec<Chapter>, usize) {
+   let mut chapters: Vec<Chapter> = Vec::new();
+   let mut merged = 0;
+
+   for note in notes {
+      let start_ms = note.timecode_ms.max(0);
+      let title = super::single_line(&note.text);
+
+      match chapters.last_mut() {
+         Some(last) if start_ms - last.start_ms < MIN_CHAPTER_MS => {
+            last.titles.push(title);
+            merged += 1;
+         }
+         // A late first note gets an intro so the list starts at 0:00
+         None if start_ms >= MIN_CHAPTER_MS => {
+            chapters.push(Chapter {
+               start_ms: 0,
+               titles: vec!["Intro".to_string()],
+            });
+            chapters.push(Chapter {
+               start_ms,
+               titles: vec![title],
+            });
+         }
+         // An early first note is moved to 0:00
+         None => chapters.push(Chapter {
+            start_ms: 0,
+            titles: vec![title],
+         }),
+         Some(_) => chapters.push(Chapter {
+            start_ms,
+            titles: vec![title],
+         }),
+      }
+   }
+
+   // The last chapter runs until the end of the recording
+   if let Some(duration_ms) = recording_duration_ms {
+      if chapters.len() > 1
+         && chapters
+            .last()
+            .is_some_and(|last| duration_ms - last.start_ms < MIN_CHAPTER_MS)
+      {
+         if let Some(last) = chapters.pop() {
+            merged += last.titles.len();
+            if let Some(previous) = chapters.last_mut() {
+               previous.titles.extend(last.titles);
+            }
+         }
+      }
+   }
+
+   (chapters, merged)
+}
+
+fn render(chapters: &[Chapter]) -> String {
+   let with_hours = chapters
+      .last()
+      .is_some_and(|chapter| chapter.start_ms >= 3_600_000);
+
+   let mut text = String::new();
+   for chapter in chapters {
+      let _ = writeln!(
+         text,
+         "{} {}",
+         format_timestamp(chapter.start_ms, with_hours),
+         chapter.titles.join(" / ")
+      );
+   }
+
+   text
+}
+
+/// `M:SS`, or `H:MM:SS` when the video is an hour or longer
+fn format_timestamp(timecode_ms: i64, with_hours: bool) -> String {
+   let (hours, minutes, seconds, _) = super::split_timecode(timecode_ms);
+
+   if with_hours {
+      format!("{}:{:02}:{:02}", hours, minutes, seconds)
+   } else {
+      format!("{}:{:02}", minutes, seconds)
+   }
+}
@@ -1 +1,6 @@
 pub mod commands;
+pub mod exporters;
+pub mod models;
+pub mod parser;
+pub mod tags;
+pub mod templates;
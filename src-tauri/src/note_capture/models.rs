@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+   pub id: u64,
+   /// Offset from the start of the recording, excluding paused time
+   pub timecode_ms: i64,
+   /// End of the marked range for notes that span a segment
+   pub end_timecode_ms: Option<i64>,
+   /// Wall-clock time the note was taken, unknown for imported notes
+   pub created_at: Option<DateTime<Utc>>,
+   /// OBS program scene when the note was captured
+   pub scene: Option<String>,
+   pub text: String,
+   /// `#tags` found in the text, lowercased and without the `#`
+   #[serde(default)]
+   pub tags: Vec<String>,
+}
+
+/// Most recently undone note, restorable until the redo window closes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoneNote {
+   pub note: Note,
+   /// Wall-clock milliseconds after which the note can no longer be restored
+   pub redo_expires_at: i64,
+   /// The note was a range marked in but not yet out
+   #[serde(default)]
+   pub was_open_range: bool,
+}
+
+/// Note text written whenever its global shortcut is pressed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSnippet {
+   pub text: String,
+   /// Accelerator such as `Ctrl+Alt+1`
+   pub shortcut: String,
+}
+
+/// User styling for a tag, keyed by tag name in app settings
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagStyle {
+   /// `#rrggbb`
+   pub color: String,
+   pub description: String,
+}
+
+pub type TagStyles = BTreeMap<String, TagStyle>;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDetails {
+   pub name: String,
+   pub color: Option<String>,
+   pub description: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteExporterDetails {
+   pub id: String,
+   pub name: String,
+   pub description: String,
+   pub enabled: bool,
+}
@@ -0,0 +1,450 @@
+use crate::{
+   note_capture::{
+      models::Note,
+      tags::parse_tags,
+      templates::{matches_rendered, LineTemplate, DEFAULT_LINE_TEMPLATE},
+   },
+   state::AppSettingsState,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+   #[error("Line {0}: text before the first note")]
+   TextBeforeFirstNote(usize),
+
+   #[error("Line {line}: invalid timecode '{timecode}', {reason}")]
+   InvalidTimecode {
+      line: usize,
+      timecode: String,
+      reason: &'static str,
+   },
+}
+
+/// Notes read from a note file, with the lines that could not be read
+pub struct ParsedNotes {
+   pub notes: Vec<Note>,
+   pub errors: Vec<ParseError>,
+}
+
+/// Templates a note file was written with. Note lines are read with the
+/// line template, and the rendered header and footer are recognised and
+/// left out of the notes.
+pub struct NoteFileTemplates<'a> {
+   pub line: &'a str,
+   pub header: &'a str,
+   pub footer: &'a str,
+}
+
+impl<'a> NoteFileTemplates<'a> {
+   pub fn from_settings(settings: &'a AppSettingsState) -> Self {
+      Self {
+         line: &settings.note_line_template,
+         header: &settings.note_header_template,
+         footer: &settings.note_footer_template,
+      }
+   }
+}
+
+impl Default for NoteFileTemplates<'_> {
+   fn default() -> Self {
+      Self {
+         line: DEFAULT_LINE_TEMPLATE,
+         header: "",
+         footer: "",
+      }
+   }
+}
+
+/// Read notes written with the line template, by default as
+/// `[HH:MM:SS.mmm] text`, or `[HH:MM:SS.mmm - HH:MM:SS.mmm] text` for
+/// ranges. An unreadable line template falls back to the default.
+///
+/// Lines that do not match the template continue the note before them,
+/// so notes spanning several lines are kept whole. Malformed timecodes are
+/// reported and their line skipped rather than failing the whole file.
+///
+/// A header or footer is only recognised when it matches the current
+/// templates, one written with an older template is reported as text
+/// before the first note or read as part of the last note.
+pub fn parse_notes(
+   contents: &str,
+   templates: &NoteFileTemplates,
+) -> ParsedNotes {
+   let mut notes: Vec<Note> = Vec::new();
+   let mut errors = Vec::new();
+
+   let line_template = LineTemplate::new(templates.line).unwrap_or_default();
+   let lines: Vec<&str> = contents.lines().collect();
+   let header_len = header_lines(&lines, templates.header, &line_template);
+   let footer_len =
+      footer_lines(&lines[header_len..], templates.footer, &line_template);
+   let body = &lines[header_len..lines.len() - footer_len];
+
+   for (index, line) in body.iter().enumerate() {
+      let line_number = header_len + index + 1;
+
+      match parse_line(line, line_number, &line_template) {
+         Ok(Some((timecode_ms, end_timecode_ms, text))) => notes.push(Note {
+            id: notes.len() as u64 + 1,
+            timecode_ms,
+            end_timecode_ms,
+            created_at: None,
+            scene: None,
+            text: text.to_string(),
+            tags: Vec::new(),
+         }),
+         Ok(None) => match notes.last_mut() {
+            Some(note) => {
+               note.text.push('\n');
+               note.text.push_str(line);
+            }
+            None if line.trim().is_empty() => {}
+            None => errors.push(ParseError::TextBeforeFirstNote(line_number)),
+         },
+         Err(err) => errors.push(err),
+      }
+   }
+
+   // Blank lines between notes belong to no note
+   for note in &mut notes {
+      note.text.truncate(note.text.trim_end().len());
+      note.tags = parse_tags(&note.text);
+   }
+
+   ParsedNotes { notes, errors }
+}
+
+/// Number of lines at the start of the file rendered from the header
+fn header_lines(
+   lines: &[&str],
+   template: &str,
+   line_template: &LineTemplate,
+) -> usize {
+   let template_lines = trimmed_lines(template);
+   if template_lines.is_empty() || template_lines.len() > lines.len() {
+      return 0;
+   }
+
+   let candidate = &lines[..template_lines.len()];
+   if is_rendered(candidate, &template_lines, line_template) {
+      candidate.len()
+   } else {
+      0
+   }
+}
+
+/// Number of lines at the end of the file rendered from the footer,
+/// including any blank lines after it
+fn footer_lines(
+   lines: &[&str],
+   template: &str,
+   line_template: &LineTemplate,
+) -> usize {
+   let template_lines = trimmed_lines(template);
+   let end = lines
+      .iter()
+      .rposition(|line| !line.trim().is_empty())
+      .map_or(0, |last| last + 1);
+   if template_lines.is_empty() || template_lines.len() > end {
+      return 0;
+   }
+
+   let candidate = &lines[end - template_lines.len()..end];
+   if is_rendered(candidate, &template_lines, line_template) {
+      lines.len() - (end - template_lines.len())
+   } else {
+      0
+   }
+}
+
+/// Template lines without the trailing blank ones, which are not told
+/// apart from blank lines between notes
+fn trimmed_lines(template: &str) -> Vec<&str> {
+   let mut lines: Vec<&str> = template.lines().collect();
+   while lines.last().is_some_and(|line| line.trim().is_empty()) {
+      lines.pop();
+   }
+
+   lines
+}
+
+/// Whether `lines` were rendered from `template_lines`. A line that reads
+/// as a note is never taken for a header or footer.
+fn is_rendered(
+   lines: &[&str],
+   template_lines: &[&str],
+   line_template: &LineTemplate,
+) -> bool {
+   lines.iter().all(|line| line_template.read(line).is_none())
+      && matches_rendered(&template_lines.join("\n"), &lines.join("\n"))
+}
+
+/// Start, end and text of a note line
+type NoteLine<'a> = (i64, Option<i64>, &'a str);
+
+/// Read a note line, `None` for continuation lines
+fn parse_line<'a>(
+   line: &'a str,
+   line_number: usize,
+   line_template: &LineTemplate,
+) -> Result<Option<NoteLine<'a>>, ParseError> {
+   let Some(values) = line_template.read(line) else {
+      return Ok(None);
+   };
+   let text = values.text;
+
+   let invalid = |timecode: &str, reason| ParseError::InvalidTimecode {
+      line: line_number,
+      timecode: timecode.to_string(),
+      reason,
+   };
+
+   let Some(timecode) = values.timecode else {
+      let seconds = values.seconds.unwrap_or_default();
+      return parse_seconds(seconds)
+         .map(|timecode_ms| Some((timecode_ms, None, text)))
+         .map_err(|reason| invalid(seconds, reason));
+   };
+
+   let Some((start, end)) = timecode.split_once(" - ") else {
+      return parse_timecode(timecode)
+         .map(|timecode_ms| Some((timecode_ms, None, text)))
+         .map_err(|reason| invalid(timecode, reason));
+   };
+
+   let start = parse_timecode(start).map_err(|r| invalid(timecode, r))?;
+   let end = parse_timecode(end).map_err(|r| invalid(timecode, r))?;
+   if end < start {
+      return Err(invalid(timecode, "range ends before it starts"));
+   }
+
+   Ok(Some((start, Some(end), text)))
+}
+
+/// `S.mmm` as rendered by `{seconds}`
+fn parse_seconds(seconds: &str) -> Result<i64, &'static str> {
+   match seconds.parse::<f64>() {
+      Ok(seconds) if seconds * 1000.0 < i64::MAX as f64 => {
+         Ok((seconds * 1000.0).round() as i64)
+      }
+      Ok(_) => Err("number too large"),
+      Err(_) => Err("expected seconds"),
+   }
+}
+
+/// `HH:MM:SS.mmm`, the milliseconds being optional
+fn parse_timecode(timecode: &str) -> Result<i64, &'static str> {
+   let (clock, milliseconds) = match timecode.split_once('.') {
+      Some((clock, fraction)) => {
+         if fraction.is_empty()
+            || fraction.len() > 3
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+         {
+            return Err("expected up to 3 digits of milliseconds");
+         }
+
+         // `.5` is half a second, not 5 milliseconds
+         let padded = format!("{:0<3}", fraction);
+         (clock, padded.parse::<i64>().unwrap_or(0))
+      }
+      None => (timecode, 0),
+   };
+
+   let parts = clock
+      .split(':')
+      .map(|part| {
+         if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            Err("expected HH:MM:SS")
+         } else {
+            part.parse::<i64>().map_err(|_| "number too large")
+         }
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+   let [hours, minutes, seconds] = parts[..] else {
+      return Err("expected HH:MM:SS");
+   };
+
+   if minutes >= 60 || seconds >= 60 {
+      return Err("minutes and seconds must be below 60");
+   }
+
+   // Scanned folders can hold any text file, so huge hours are an error
+   // rather than an overflow
+   hours
+      .checked_mul(60)
+      .and_then(|total| total.checked_add(minutes))
+      .and_then(|total| total.checked_mul(60))
+      .and_then(|total| total.checked_add(seconds))
+      .and_then(|total| total.checked_mul(1000))
+      .and_then(|total| total.checked_add(milliseconds))
+      .ok_or("number too large")
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+   use crate::note_capture::templates::render_line;
+
+   fn note(id: u64, timecode_ms: i64, text: &str) -> Note {
+      Note {
+         id,
+         timecode_ms,
+         end_timecode_ms: None,
+         created_at: None,
+         scene: None,
+         text: text.to_string(),
+         tags: parse_tags(text),
+      }
+   }
+
+   fn render(template: &str, notes: &[Note]) -> String {
+      notes
+         .iter()
+         .enumerate()
+         .map(|(index, note)| render_line(template, note, index + 1) + "\n")
+         .collect()
+   }
+
+   #[test]
+   fn reads_back_rendered_notes() {
+      let mut range = note(2, 65_250, "range #Bug");
+      range.end_timecode_ms = Some(70_000);
+      let notes = vec![
+         note(1, 1_500, "first"),
+         range,
+         note(3, 3_723_004, "two\nlines"),
+      ];
+
+      let parsed = parse_notes(
+         &render(DEFAULT_LINE_TEMPLATE, &notes),
+         &NoteFileTemplates::default(),
+      );
+
+      assert!(parsed.errors.is_empty());
+      assert_eq!(parsed.notes, notes);
+   }
+
+   #[test]
+   fn reads_back_custom_line_template() {
+      let notes = vec![note(1, 2_000, "hello #tag")];
+
+      let parsed = parse_notes(
+         &render("[{timecode}]  {text}", &notes),
+         &NoteFileTemplates {
+            line: "[{timecode}]  {text}",
+            ..Default::default()
+         },
+      );
+
+      assert_eq!(parsed.notes.len(), 1);
+      assert_eq!(parsed.notes[0].text, "hello #tag");
+      assert_eq!(parsed.notes[0].tags, vec!["tag"]);
+   }
+
+   #[test]
+   fn reads_back_team_line_layouts() {
+      let mut range = note(2, 65_250, "range #Bug");
+      range.end_timecode_ms = Some(70_000);
+      let notes = vec![note(1, 1_500, "first"), range];
+
+      for template in [
+         "{index}. {text} @ {timecode}",
+         "{seconds}s | {scene} | {text}",
+      ] {
+         let parsed = parse_notes(
+            &render(template, &notes),
+            &NoteFileTemplates {
+               line: template,
+               ..Default::default()
+            },
+         );
+
+         assert!(parsed.errors.is_empty(), "{}", template);
+         assert_eq!(parsed.notes.len(), 2, "{}", template);
+         assert_eq!(parsed.notes[1].text, "range #Bug", "{}", template);
+         assert_eq!(parsed.notes[1].timecode_ms, 65_250, "{}", template);
+      }
+   }
+
+   #[test]
+   fn skips_rendered_header_and_footer() {
+      let templates = NoteFileTemplates {
+         line: DEFAULT_LINE_TEMPLATE,
+         header: "Recording {recording}\nStarted {started_at}",
+         footer: "{note_count} notes, {duration}",
+      };
+      let contents = "Recording a.mkv\nStarted 2026-01-01 10:00:00\n\
+         [00:00:01.000] one\n[00:00:02.000] two\n1 notes, 00:00:03.000\n\n";
+
+      let parsed = parse_notes(contents, &templates);
+
+      assert!(parsed.errors.is_empty());
+      assert_eq!(parsed.notes.len(), 2);
+      assert_eq!(parsed.notes[1].text, "two");
+   }
+
+   #[test]
+   fn header_only_file_has_no_notes_or_errors() {
+      let templates = NoteFileTemplates {
+         line: DEFAULT_LINE_TEMPLATE,
+         header: "Recording {recording}",
+         footer: "",
+      };
+
+      let parsed = parse_notes("Recording a.mkv\n", &templates);
+
+      assert!(parsed.notes.is_empty());
+      assert!(parsed.errors.is_empty());
+   }
+
+   #[test]
+   fn unknown_text_before_notes_is_an_error() {
+      let parsed =
+         parse_notes("intro\n[00:00:01] one\n", &NoteFileTemplates::default());
+
+      assert_eq!(parsed.notes.len(), 1);
+      assert!(matches!(
+         parsed.errors[..],
+         [ParseError::TextBeforeFirstNote(1)]
+      ));
+   }
+
+   #[test]
+   fn header_never_takes_a_note_line() {
+      let templates = NoteFileTemplates {
+         line: DEFAULT_LINE_TEMPLATE,
+         header: "{recording}",
+         footer: "{duration}",
+      };
+
+      let parsed = parse_notes("[00:00:01] only\n", &templates);
+
+      assert_eq!(parsed.notes.len(), 1);
+   }
+
+   #[test]
+   fn parses_timecodes() {
+      assert_eq!(parse_timecode("01:02:03.5"), Ok(3_723_500));
+      assert_eq!(parse_timecode("00:00:01.005"), Ok(1_005));
+      assert_eq!(parse_timecode("1:2:3"), Ok(3_723_000));
+      assert!(parse_timecode("00:60:00").is_err());
+      assert!(parse_timecode("00:00:01.1234").is_err());
+      assert!(parse_timecode("00:01").is_err());
+   }
+
+   #[test]
+   fn huge_hours_are_an_error() {
+      assert_eq!(
+         parse_timecode("9223372036854775807:00:00"),
+         Err("number too large")
+      );
+
+      let parsed = parse_notes(
+         "[9223372036854775:00:00] too long\n",
+         &NoteFileTemplates::default(),
+      );
+      assert!(parsed.notes.is_empty());
+      assert_eq!(parsed.errors.len(), 1);
+   }
+}
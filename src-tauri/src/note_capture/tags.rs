@@ -0,0 +1,68 @@
+use tauri_plugin_store::StoreExt;
+
+/// Store key holding tags from past notes, most recently used first
+const USED_TAGS_KEY: &str = "used_tags";
+
+/// Enough for autocompletion without the store growing forever
+const MAX_USED_TAGS: usize = 200;
+
+/// `#tags` in note text, lowercased and without the `#`, in order of first
+/// use. A tag has to start a word and contain a letter, so `issue#4` and
+/// `#1` stay plain text.
+pub fn parse_tags(text: &str) -> Vec<String> {
+   let mut tags: Vec<String> = Vec::new();
+
+   for word in text.split_whitespace() {
+      let Some(rest) = word.strip_prefix('#') else {
+         continue;
+      };
+
+      let tag = rest
+         .chars()
+         .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+         .collect::<String>()
+         .trim_end_matches('-')
+         .to_lowercase();
+
+      if tag.chars().any(char::is_alphabetic) && !tags.contains(&tag) {
+         tags.push(tag);
+      }
+   }
+
+   tags
+}
+
+/// Whether `name` would be read back as exactly that tag
+pub fn is_valid_tag(name: &str) -> bool {
+   parse_tags(&format!("#{}", name)) == [name]
+}
+
+/// Tags from past notes, most recently used first
+pub fn used_tags(app_handle: &tauri::AppHandle) -> Vec<String> {
+   app_handle
+      .store(crate::constants::Store::AppSettings.as_ref())
+      .ok()
+      .and_then(|store| store.get(USED_TAGS_KEY))
+      .and_then(|v| serde_json::from_value(v).ok())
+      .unwrap_or_default()
+}
+
+/// Move `tags` to the front of the used tags
+pub fn remember_tags(app_handle: &tauri::AppHandle, tags: &[String]) {
+   if tags.is_empty() {
+      return;
+   }
+
+   let Ok(store) =
+      app_handle.store(crate::constants::Store::AppSettings.as_ref())
+   else {
+      return;
+   };
+
+   let mut used = used_tags(app_handle);
+   used.retain(|tag| !tags.contains(tag));
+   used.splice(0..0, tags.iter().cloned());
+   used.truncate(MAX_USED_TAGS);
+
+   store.set(USED_TAGS_KEY, serde_json::json!(used));
+}
@@ -0,0 +1,454 @@
+use chrono::{DateTime, Local, Utc};
+
+use crate::note_capture::{
+   exporters::{format_timecode, tag_list},
+   models::Note,
+};
+
+pub const DEFAULT_LINE_TEMPLATE: &str = "[{timecode}] {text}";
+
+/// Placeholders available to each note line
+pub const LINE_PLACEHOLDERS: &[&str] = &[
+   "timecode",
+   "seconds",
+   "wallclock",
+   "text",
+   "scene",
+   "index",
+   "tags",
+];
+
+/// Placeholders available to the header, written when recording starts
+pub const HEADER_PLACEHOLDERS: &[&str] =
+   &["recording", "started_at", "obs_version"];
+
+/// Placeholders available to the footer, written when recording stops
+pub const FOOTER_PLACEHOLDERS: &[&str] = &[
+   "recording",
+   "started_at",
+   "obs_version",
+   "note_count",
+   "duration",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+   #[error("Unknown placeholder '{{{0}}}', expected one of {1}")]
+   UnknownPlaceholder(String, String),
+
+   #[error("Placeholder '{{{0}' is missing its closing '}}'")]
+   UnclosedPlaceholder(String),
+
+   #[error(
+      "Note lines must include '{{timecode}}' or '{{seconds}}' to be read back"
+   )]
+   MissingTimecode,
+
+   #[error("Note lines must include '{{text}}'")]
+   MissingText,
+}
+
+enum Segment<'a> {
+   Literal(&'a str),
+   Placeholder(&'a str),
+}
+
+/// Recording details available to the header and footer templates
+pub struct FileTemplateValues<'a> {
+   pub recording: &'a str,
+   pub started_at: Option<DateTime<Utc>>,
+   pub obs_version: Option<&'a str>,
+   pub note_count: usize,
+   pub duration_ms: Option<i64>,
+}
+
+pub fn validate(template: &str, allowed: &[&str]) -> Result<(), TemplateError> {
+   for segment in parse(template)? {
+      if let Segment::Placeholder(name) = segment {
+         if !allowed.contains(&name) {
+            return Err(TemplateError::UnknownPlaceholder(
+               name.to_string(),
+               allowed
+                  .iter()
+                  .map(|p| format!("{{{}}}", p))
+                  .collect::<Vec<_>>()
+                  .join(", "),
+            ));
+         }
+      }
+   }
+
+   Ok(())
+}
+
+/// Check a line template renders note lines the note file parser can
+/// read back, which needs the note text and when it was taken
+pub fn validate_line_template(template: &str) -> Result<(), TemplateError> {
+   validate(template, LINE_PLACEHOLDERS)?;
+
+   let segments = parse(template)?;
+   let has = |name: &str| {
+      segments.iter().any(
+         |segment| matches!(segment, Segment::Placeholder(p) if *p == name),
+      )
+   };
+
+   if !has("text") {
+      return Err(TemplateError::MissingText);
+   }
+   if !has("timecode") && !has("seconds") {
+      return Err(TemplateError::MissingTimecode);
+   }
+
+   Ok(())
+}
+
+/// A line template prepared for reading note lines back
+pub struct LineTemplate<'a> {
+   segments: Vec<Segment<'a>>,
+}
+
+/// Placeholders of a note line needed to read it back, as written
+#[derive(Default)]
+pub struct LineValues<'a> {
+   pub timecode: Option<&'a str>,
+   pub seconds: Option<&'a str>,
+   pub text: &'a str,
+}
+
+impl<'a> LineTemplate<'a> {
+   /// `None` unless the template passes `validate_line_template`
+   pub fn new(template: &'a str) -> Option<Self> {
+      validate_line_template(template).ok()?;
+
+      Some(Self {
+         segments: parse(template).ok()?,
+      })
+   }
+
+   /// Values of a line rendered from the template, `None` for any other
+   /// line. A note spanning several lines is only read back whole when
+   /// `{text}` ends the template, as otherwise its first line is not
+   /// complete.
+   pub fn read<'b>(&self, line: &'b str) -> Option<LineValues<'b>> {
+      let mut values = LineValues::default();
+      read_segments(&self.segments, line, &mut values).then_some(values)
+   }
+}
+
+impl Default for LineTemplate<'_> {
+   fn default() -> Self {
+      Self {
+         segments: parse(DEFAULT_LINE_TEMPLATE).unwrap_or_default(),
+      }
+   }
+}
+
+fn read_segments<'b>(
+   segments: &[Segment],
+   line: &'b str,
+   values: &mut LineValues<'b>,
+) -> bool {
+   let Some((segment, rest)) = segments.split_first() else {
+      return line.is_empty();
+   };
+   let name = match segment {
+      Segment::Literal(literal) => {
+         return line
+            .strip_prefix(literal)
+            .is_some_and(|line| read_segments(rest, line, values));
+      }
+      Segment::Placeholder(name) => *name,
+   };
+
+   // The note text and numbers take as much as they can, so a range is
+   // not cut at its start and text may contain the literals that follow.
+   // Scene and tags take as little as they can.
+   let mut ends: Vec<usize> = line
+      .char_indices()
+      .map(|(index, _)| index)
+      .chain(std::iter::once(line.len()))
+      .collect();
+   if matches!(name, "text" | "timecode" | "seconds" | "index") {
+      ends.reverse();
+   }
+
+   for end in ends {
+      let value = &line[..end];
+      if is_placeholder_value(name, value)
+         && read_segments(rest, &line[end..], values)
+      {
+         match name {
+            "timecode" => values.timecode = Some(value),
+            "seconds" => values.seconds = Some(value),
+            "text" => values.text = value,
+            _ => {}
+         }
+         return true;
+      }
+   }
+
+   false
+}
+
+/// Whether `value` looks like what the placeholder renders. Timecodes only
+/// need the `H:M:S` shape, so out of range ones are still found and
+/// reported by the parser rather than read as note text.
+fn is_placeholder_value(name: &str, value: &str) -> bool {
+   fn is_number(value: &str) -> bool {
+      !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+   }
+
+   fn is_clock(value: &str) -> bool {
+      let clock = match value.split_once('.') {
+         Some((clock, fraction)) if is_number(fraction) => clock,
+         Some(_) => return false,
+         None => value,
+      };
+
+      clock.split(':').count() == 3 && clock.split(':').all(is_number)
+   }
+
+   match name {
+      "timecode" => match value.split_once(" - ") {
+         Some((start, end)) => is_clock(start) && is_clock(end),
+         None => is_clock(value),
+      },
+      "seconds" => {
+         let (whole, fraction) = value.split_once('.').unwrap_or((value, "0"));
+         is_number(whole) && is_number(fraction)
+      }
+      "index" => is_number(value),
+      "wallclock" => value
+         .chars()
+         .all(|c| c.is_ascii_digit() || matches!(c, '-' | ':' | ' ')),
+      _ => true,
+   }
+}
+
+/// Whether `text` could have been rendered from `template`, each
+/// placeholder standing for any text
+pub fn matches_rendered(template: &str, text: &str) -> bool {
+   match parse(template) {
+      Ok(segments) => matches_segments(&segments, text),
+      Err(_) => template == text,
+   }
+}
+
+fn matches_segments(segments: &[Segment], text: &str) -> bool {
+   match segments.split_first() {
+      None => text.is_empty(),
+      Some((Segment::Literal(literal), rest)) => text
+         .strip_prefix(literal)
+         .is_some_and(|text| matches_segments(rest, text)),
+      Some((Segment::Placeholder(_), rest)) => text
+         .char_indices()
+         .map(|(index, _)| index)
+         .chain(std::iter::once(text.len()))
+         .any(|index| matches_segments(rest, &text[index..])),
+   }
+}
+
+/// Render a note line, `index` counting from 1
+pub fn render_line(template: &str, note: &Note, index: usize) -> String {
+   render(template, |name| match name {
+      // Ranges read `start - end`, which the note file parser understands
+      "timecode" => match note.end_timecode_ms {
+         Some(end) => format!(
+            "{} - {}",
+            format_timecode(note.timecode_ms),
+            format_timecode(end)
+         ),
+         None => format_timecode(note.timecode_ms),
+      },
+      "seconds" => format!("{:.3}", note.timecode_ms.max(0) as f64 / 1000.0),
+      "wallclock" => note.created_at.map(format_wallclock).unwrap_or_default(),
+      "text" => note.text.clone(),
+      "scene" => note.scene.clone().unwrap_or_default(),
+      "index" => index.to_string(),
+      "tags" => tag_list(note),
+      _ => String::new(),
+   })
+}
+
+pub fn render_file_template(
+   template: &str,
+   values: &FileTemplateValues,
+) -> String {
+   render(template, |name| match name {
+      "recording" => values.recording.to_string(),
+      "started_at" => {
+         values.started_at.map(format_wallclock).unwrap_or_default()
+      }
+      "obs_version" => values.obs_version.unwrap_or_default().to_string(),
+      "note_count" => values.note_count.to_string(),
+      "duration" => values.duration_ms.map(format_timecode).unwrap_or_default(),
+      _ => String::new(),
+   })
+}
+
+pub fn format_wallclock(date: DateTime<Utc>) -> String {
+   date
+      .with_timezone(&Local)
+      .format("%Y-%m-%d %H:%M:%S")
+      .to_string()
+}
+
+/// Templates are validated when saved, one that fails to parse is written
+/// out as is rather than losing the note
+fn render(template: &str, value: impl Fn(&str) -> String) -> String {
+   let Ok(segments) = parse(template) else {
+      return template.to_string();
+   };
+
+   segments
+      .into_iter()
+      .map(|segment| match segment {
+         Segment::Literal(text) => text.to_string(),
+         Segment::Placeholder(name) => value(name),
+      })
+      .collect()
+}
+
+/// Split on `{placeholder}`s, `{{` and `}}` being literal braces
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, TemplateError> {
+   let mut segments = Vec::new();
+   let mut rest = template;
+
+   while let Some(open) = rest.find(['{', '}']) {
+      if open > 0 {
+         segments.push(Segment::Literal(&rest[..open]));
+      }
+
+      let brace = &rest[open..open + 1];
+      let after = &rest[open + 1..];
+
+      if after.starts_with(brace) {
+         segments.push(Segment::Literal(brace));
+         rest = &after[1..];
+      } else if brace == "}" {
+         segments.push(Segment::Literal(brace));
+         rest = after;
+      } else {
+         let Some(close) = after.find('}') else {
+            return Err(TemplateError::UnclosedPlaceholder(after.to_string()));
+         };
+
+         segments.push(Segment::Placeholder(after[..close].trim()));
+         rest = &after[close + 1..];
+      }
+   }
+
+   if !rest.is_empty() {
+      segments.push(Segment::Literal(rest));
+   }
+
+   Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn note(text: &str) -> Note {
+      Note {
+         id: 1,
+         timecode_ms: 61_500,
+         end_timecode_ms: None,
+         created_at: None,
+         scene: Some("Main".to_string()),
+         text: text.to_string(),
+         tags: vec!["bug".to_string()],
+      }
+   }
+
+   #[test]
+   fn renders_line_placeholders() {
+      let line =
+         render_line("[{timecode}] {index} {scene}: {text}", &note("x"), 3);
+
+      assert_eq!(line, "[00:01:01.500] 3 Main: x");
+   }
+
+   #[test]
+   fn renders_escaped_braces() {
+      assert_eq!(render_line("{{{text}}}", &note("x"), 1), "{x}");
+   }
+
+   #[test]
+   fn rejects_unknown_and_unclosed_placeholders() {
+      assert!(matches!(
+         validate("{nope}", LINE_PLACEHOLDERS),
+         Err(TemplateError::UnknownPlaceholder(..))
+      ));
+      assert!(matches!(
+         validate("{text", LINE_PLACEHOLDERS),
+         Err(TemplateError::UnclosedPlaceholder(..))
+      ));
+   }
+
+   #[test]
+   fn line_templates_must_be_readable() {
+      assert!(validate_line_template(DEFAULT_LINE_TEMPLATE).is_ok());
+      assert!(validate_line_template("[{timecode}] {scene} {text}").is_ok());
+      assert!(validate_line_template("{text} at {seconds}s").is_ok());
+      assert!(matches!(
+         validate_line_template("{index}. {text}"),
+         Err(TemplateError::MissingTimecode)
+      ));
+      assert!(matches!(
+         validate_line_template("[{timecode}] {scene}"),
+         Err(TemplateError::MissingText)
+      ));
+   }
+
+   #[test]
+   fn reads_lines_it_rendered() {
+      let template =
+         LineTemplate::new("{index}. {text} ({scene}) @ {timecode}")
+            .expect("readable template");
+      let line = render_line(
+         "{index}. {text} ({scene}) @ {timecode}",
+         &note("a (b) c"),
+         12,
+      );
+
+      let values = template.read(&line).expect("rendered line");
+
+      assert_eq!(values.text, "a (b) c");
+      assert_eq!(values.timecode, Some("00:01:01.500"));
+      assert!(template.read("12. no timecode").is_none());
+   }
+
+   #[test]
+   fn reads_ranges_whole() {
+      let values = LineTemplate::new("{timecode} {text}")
+         .and_then(|template| {
+            template.read("00:00:01.000 - 00:00:02.000 - dash")
+         })
+         .expect("rendered line");
+
+      assert_eq!(values.timecode, Some("00:00:01.000 - 00:00:02.000"));
+      assert_eq!(values.text, "- dash");
+   }
+
+   #[test]
+   fn matches_what_it_rendered() {
+      let template = "Recording {recording}, OBS {obs_version}";
+      let rendered = render_file_template(
+         template,
+         &FileTemplateValues {
+            recording: "a.mkv",
+            started_at: None,
+            obs_version: Some("31.0"),
+            note_count: 0,
+            duration_ms: None,
+         },
+      );
+
+      assert!(matches_rendered(template, &rendered));
+      assert!(matches_rendered(template, "Recording , OBS "));
+      assert!(!matches_rendered(template, "Recorded a.mkv, OBS 31.0"));
+      assert!(matches_rendered("{{{recording}}}", "{a}"));
+   }
+}
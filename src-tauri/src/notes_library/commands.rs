@@ -0,0 +1,57 @@
+use tauri_plugin_opener::OpenerExt;
+
+use crate::notes_library::{
+   models::{NoteSearchQuery, NoteSearchResult},
+   service,
+};
+
+/// Search the library as last scanned, see `scan_notes_library`
+#[tauri::command]
+pub async fn search_notes(
+   app_handle: tauri::AppHandle,
+   query: NoteSearchQuery,
+) -> Result<Vec<NoteSearchResult>, String> {
+   let connection = service::open(&app_handle)
+      .map_err(|e| format!("Failed to open notes library: {}", e))?;
+
+   service::search(&connection, &query)
+      .map_err(|e| format!("Failed to search notes: {}", e))
+}
+
+/// Index notes added or edited outside Clip Mark since the last scan. The
+/// recording folders are also scanned whenever OBS connects.
+#[tauri::command]
+pub async fn scan_notes_library(
+   app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+   tauri::async_runtime::spawn_blocking(move || service::refresh(&app_handle))
+      .await
+      .map_err(|e| format!("Failed to scan notes library: {}", e))?
+      .map_err(|e| format!("Failed to scan notes library: {}", e))
+}
+
+/// Show a recording in its folder, or open the folder if the recording has
+/// been moved or deleted
+#[tauri::command]
+pub async fn reveal_recording(
+   app_handle: tauri::AppHandle,
+   recording_path: String,
+) -> Result<(), String> {
+   let path = std::path::Path::new(&recording_path);
+
+   if path.is_file() {
+      return app_handle
+         .opener()
+         .reveal_item_in_dir(path)
+         .map_err(|e| format!("Failed to reveal recording: {}", e));
+   }
+
+   let Some(folder) = path.parent().filter(|folder| folder.is_dir()) else {
+      return Err(format!("Recording folder not found: {}", recording_path));
+   };
+
+   app_handle
+      .opener()
+      .open_path(folder.to_string_lossy(), None::<&str>)
+      .map_err(|e| format!("Failed to open recording folder: {}", e))
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::note_capture::models::Note;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSearchQuery {
+   /// Words to find in the note text, matched as prefixes
+   pub text: Option<String>,
+   /// Notes must carry every one of these tags
+   #[serde(default)]
+   pub tags: Vec<String>,
+   pub from: Option<DateTime<Utc>>,
+   pub to: Option<DateTime<Utc>>,
+   pub limit: Option<u32>,
+}
+
+/// A note found in the library, with the recording it belongs to
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSearchResult {
+   pub recording_path: String,
+   pub recording_started_at: Option<DateTime<Utc>>,
+   /// `file://` URL of the recording at the note, as `#t=seconds`
+   pub link: String,
+   /// Text match relevance, higher is better, only set for text queries
+   pub score: Option<f64>,
+   pub note: Note,
+   /// Notes of the same recording just before and after this one
+   pub before: Vec<Note>,
+   pub after: Vec<Note>,
+}
@@ -0,0 +1,573 @@
+use std::{
+   collections::{BTreeSet, HashMap},
+   path::{Path, PathBuf},
+   sync::Mutex,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::warn;
+use rusqlite::{params, params_from_iter, types::Value, Connection, Row};
+use serde::Deserialize;
+use tauri::Manager;
+
+use crate::{
+   note_capture::{
+      exporters::{file_url, RECORDING_EXTENSIONS},
+      models::Note,
+      parser::{parse_notes, NoteFileTemplates},
+   },
+   notes_library::models::{NoteSearchQuery, NoteSearchResult},
+   state::AppSettingsState,
+   GlobalState,
+};
+
+const DATABASE_FILE: &str = "notes-library.sqlite3";
+
+/// Results returned when the query does not set a limit
+const DEFAULT_SEARCH_LIMIT: u32 = 100;
+
+/// Notes returned either side of a match
+const CONTEXT_NOTES: u32 = 2;
+
+/// Columns read by `note_from_row`, for a `notes` table aliased `n`
+const NOTE_COLUMNS: &str = "n.note_id, n.timecode_ms, n.end_timecode_ms,
+   n.created_at, n.scene, n.text,
+   (SELECT group_concat(tag, ' ') FROM note_tags t WHERE t.note_id = n.id)";
+
+/// Each entry brings the schema from the previous version to the next,
+/// `user_version` records how many have run
+const MIGRATIONS: &[&str] = &[
+   r#"
+   CREATE TABLE recordings (
+      id INTEGER PRIMARY KEY,
+      path TEXT NOT NULL UNIQUE,
+      started_at TEXT,
+      duration_ms INTEGER,
+      obs_version TEXT
+   );
+
+   CREATE TABLE notes (
+      id INTEGER PRIMARY KEY,
+      recording_id INTEGER NOT NULL
+         REFERENCES recordings (id) ON DELETE CASCADE,
+      note_id INTEGER NOT NULL,
+      timecode_ms INTEGER NOT NULL,
+      end_timecode_ms INTEGER,
+      created_at TEXT,
+      scene TEXT,
+      text TEXT NOT NULL
+   );
+   CREATE INDEX notes_recording_id ON notes (recording_id);
+
+   CREATE TABLE note_tags (
+      note_id INTEGER NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+      tag TEXT NOT NULL
+   );
+   CREATE INDEX note_tags_tag ON note_tags (tag);
+
+   CREATE VIRTUAL TABLE notes_fts
+      USING fts5 (text, content = 'notes', content_rowid = 'id');
+
+   CREATE TRIGGER notes_fts_insert AFTER INSERT ON notes BEGIN
+      INSERT INTO notes_fts (rowid, text) VALUES (new.id, new.text);
+   END;
+   CREATE TRIGGER notes_fts_delete AFTER DELETE ON notes BEGIN
+      INSERT INTO notes_fts (notes_fts, rowid, text)
+         VALUES ('delete', old.id, old.text);
+   END;
+   CREATE TRIGGER notes_fts_update AFTER UPDATE ON notes BEGIN
+      INSERT INTO notes_fts (notes_fts, rowid, text)
+         VALUES ('delete', old.id, old.text);
+      INSERT INTO notes_fts (rowid, text) VALUES (new.id, new.text);
+   END;
+"#,
+   r#"
+   ALTER TABLE recordings ADD COLUMN indexed_at_ms INTEGER;
+"#,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum LibraryError {
+   #[error("Failed to find the app data directory: {0}")]
+   DataDir(#[from] tauri::Error),
+
+   #[error(transparent)]
+   Io(#[from] std::io::Error),
+
+   #[error(transparent)]
+   Database(#[from] rusqlite::Error),
+
+   #[error("Failed to lock app settings")]
+   Settings,
+}
+
+/// A finished recording and its notes, as added to the library
+pub struct RecordingEntry<'a> {
+   pub path: &'a str,
+   pub started_at: Option<DateTime<Utc>>,
+   pub duration_ms: Option<i64>,
+   pub obs_version: Option<&'a str>,
+   pub notes: &'a [Note],
+}
+
+/// Open the library in the app data directory, creating or upgrading it
+/// as needed
+pub fn open(app_handle: &tauri::AppHandle) -> Result<Connection, LibraryError> {
+   let data_dir = app_handle.path().app_data_dir()?;
+   std::fs::create_dir_all(&data_dir)?;
+
+   let mut connection = Connection::open(data_dir.join(DATABASE_FILE))?;
+   connection.pragma_update(None, "foreign_keys", true)?;
+   migrate(&mut connection)?;
+
+   Ok(connection)
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+   let version: usize =
+      connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+   for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+      let transaction = connection.transaction()?;
+      transaction.execute_batch(migration)?;
+      transaction.pragma_update(None, "user_version", index + 1)?;
+      transaction.commit()?;
+   }
+
+   Ok(())
+}
+
+/// Add a finished recording to the library, replacing an earlier entry
+/// for the same path
+pub fn add_recording(
+   app_handle: &tauri::AppHandle,
+   recording: &RecordingEntry,
+) -> Result<(), LibraryError> {
+   let mut connection = open(app_handle)?;
+   insert_recording(&mut connection, recording)?;
+
+   Ok(())
+}
+
+fn insert_recording(
+   connection: &mut Connection,
+   recording: &RecordingEntry,
+) -> rusqlite::Result<()> {
+   let transaction = connection.transaction()?;
+
+   transaction
+      .execute("DELETE FROM recordings WHERE path = ?1", [recording.path])?;
+   transaction.execute(
+      "INSERT INTO recordings (path, started_at, duration_ms, obs_version,
+         indexed_at_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+      params![
+         recording.path,
+         recording.started_at.map(format_date),
+         recording.duration_ms,
+         recording.obs_version,
+         Utc::now().timestamp_millis(),
+      ],
+   )?;
+   let recording_id = transaction.last_insert_rowid();
+
+   {
+      let mut insert_note = transaction.prepare(
+         "INSERT INTO notes (recording_id, note_id, timecode_ms,
+            end_timecode_ms, created_at, scene, text)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+      )?;
+      let mut insert_tag = transaction
+         .prepare("INSERT INTO note_tags (note_id, tag) VALUES (?1, ?2)")?;
+
+      for note in recording.notes {
+         let note_row_id = insert_note.insert(params![
+            recording_id,
+            note.id as i64,
+            note.timecode_ms,
+            note.end_timecode_ms,
+            note.created_at.map(format_date),
+            note.scene,
+            note.text,
+         ])?;
+
+         for tag in &note.tags {
+            insert_tag.execute(params![note_row_id, tag])?;
+         }
+      }
+   }
+
+   transaction.commit()
+}
+
+/// The parts of a `.notes.json` sidecar the library keeps
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotesDocument {
+   recording: NotesDocumentRecording,
+   notes: Vec<Note>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotesDocumentRecording {
+   obs_version: Option<String>,
+   started_at: Option<DateTime<Utc>>,
+   duration_ms: Option<i64>,
+}
+
+/// Scan the recording folders with the current note file templates,
+/// including the one OBS records to once connected
+pub fn refresh(app_handle: &tauri::AppHandle) -> Result<(), LibraryError> {
+   let settings = app_handle
+      .state::<Mutex<AppSettingsState>>()
+      .lock()
+      .map(|settings| settings.clone())
+      .map_err(|_| LibraryError::Settings)?;
+   let record_directory = app_handle
+      .state::<GlobalState>()
+      .record_directory
+      .lock()
+      .ok()
+      .and_then(|directory| directory.clone())
+      .map(PathBuf::from);
+
+   let mut connection = open(app_handle)?;
+   scan_recording_directories(
+      &mut connection,
+      &NoteFileTemplates::from_settings(&settings),
+      record_directory.as_deref(),
+   )?;
+
+   Ok(())
+}
+
+/// Reading every sidecar can take a while, so refreshing runs off the
+/// event loop
+pub fn spawn_refresh(app_handle: &tauri::AppHandle) {
+   let app_handle = app_handle.clone();
+
+   tauri::async_runtime::spawn_blocking(move || {
+      if let Err(err) = refresh(&app_handle) {
+         warn!("Failed to scan recording directories: {}", err);
+      }
+   });
+}
+
+/// Index note sidecars in the folders Clip Mark has recorded to, when they
+/// are new or have changed since last indexed. This picks up notes edited
+/// by hand and recordings made before the library existed.
+pub fn scan_recording_directories(
+   connection: &mut Connection,
+   templates: &NoteFileTemplates,
+   record_directory: Option<&Path>,
+) -> rusqlite::Result<()> {
+   let indexed_at: HashMap<String, Option<i64>> = connection
+      .prepare("SELECT path, indexed_at_ms FROM recordings")?
+      .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+      .collect::<rusqlite::Result<_>>()?;
+
+   let directories: BTreeSet<PathBuf> = indexed_at
+      .keys()
+      .filter_map(|path| Path::new(path).parent().map(Path::to_path_buf))
+      .chain(record_directory.map(Path::to_path_buf))
+      .collect();
+
+   for directory in directories {
+      let Ok(entries) = std::fs::read_dir(&directory) else {
+         continue;
+      };
+
+      for sidecar in entries.flatten().map(|entry| entry.path()) {
+         let Some((stem, is_json)) = sidecar_stem(&sidecar) else {
+            continue;
+         };
+
+         // The JSON sidecar holds more than the note file, so it wins
+         if !is_json && directory.join(format!("{}.notes.json", stem)).is_file()
+         {
+            continue;
+         }
+
+         let Some(recording_path) = RECORDING_EXTENSIONS
+            .iter()
+            .map(|ext| directory.join(format!("{}.{}", stem, ext)))
+            .find(|path| path.is_file())
+         else {
+            continue;
+         };
+
+         let modified_at = std::fs::metadata(&sidecar)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|modified| DateTime::<Utc>::from(modified).timestamp_millis());
+         let recording_key = recording_path.to_string_lossy().to_string();
+         let is_current = match (indexed_at.get(&recording_key), modified_at) {
+            (Some(Some(indexed)), Some(modified)) => *indexed >= modified,
+            _ => false,
+         };
+         if is_current {
+            continue;
+         }
+
+         let Some(document) =
+            read_sidecar(&sidecar, &recording_path, is_json, templates)
+         else {
+            continue;
+         };
+
+         if let Err(err) = insert_recording(
+            connection,
+            &RecordingEntry {
+               path: &recording_key,
+               started_at: document.recording.started_at,
+               duration_ms: document.recording.duration_ms,
+               obs_version: document.recording.obs_version.as_deref(),
+               notes: &document.notes,
+            },
+         ) {
+            warn!("Failed to index {}: {}", sidecar.display(), err);
+         }
+      }
+   }
+
+   Ok(())
+}
+
+/// Recording name a note sidecar belongs to, and whether it is the JSON one
+fn sidecar_stem(path: &Path) -> Option<(String, bool)> {
+   let name = path.file_name()?.to_str()?;
+
+   if let Some(stem) = name.strip_suffix(".notes.json") {
+      Some((stem.to_string(), true))
+   } else {
+      name
+         .strip_suffix(".txt")
+         .map(|stem| (stem.to_string(), false))
+   }
+}
+
+/// Notes from a sidecar, skipping text files that are not note files. A
+/// note file does not say when its recording started, so the recording
+/// file's creation time stands in, or its last write on file systems that
+/// do not keep one.
+fn read_sidecar(
+   path: &Path,
+   recording_path: &Path,
+   is_json: bool,
+   templates: &NoteFileTemplates,
+) -> Option<NotesDocument> {
+   let contents = std::fs::read_to_string(path).ok()?;
+
+   if is_json {
+      return serde_json::from_str(&contents)
+         .map_err(|err| warn!("Failed to read {}: {}", path.display(), err))
+         .ok();
+   }
+
+   let parsed = parse_notes(&contents, templates);
+   if parsed.notes.is_empty() || !parsed.errors.is_empty() {
+      return None;
+   }
+
+   Some(NotesDocument {
+      recording: NotesDocumentRecording {
+         obs_version: None,
+         started_at: std::fs::metadata(recording_path)
+            .and_then(|metadata| {
+               metadata.created().or_else(|_| metadata.modified())
+            })
+            .ok()
+            .map(DateTime::<Utc>::from),
+         duration_ms: None,
+      },
+      notes: parsed.notes,
+   })
+}
+
+/// Notes matching every part of the query with the notes around them,
+/// best text matches first or newest first when there is no text to match
+pub fn search(
+   connection: &Connection,
+   query: &NoteSearchQuery,
+) -> rusqlite::Result<Vec<NoteSearchResult>> {
+   let text_query = query
+      .text
+      .as_deref()
+      .map(fts_query)
+      .filter(|text| !text.is_empty());
+
+   let mut sql = format!(
+      "SELECT r.path, r.started_at, r.id, n.id, {}, {}
+      FROM notes n
+      JOIN recordings r ON r.id = n.recording_id",
+      if text_query.is_some() {
+         "bm25(notes_fts)"
+      } else {
+         "NULL"
+      },
+      NOTE_COLUMNS
+   );
+   let mut conditions: Vec<&str> = Vec::new();
+   let mut values: Vec<Value> = Vec::new();
+
+   if let Some(text_query) = &text_query {
+      sql.push_str(" JOIN notes_fts ON notes_fts.rowid = n.id");
+      conditions.push("notes_fts MATCH ?");
+      values.push(Value::Text(text_query.clone()));
+   }
+
+   for tag in &query.tags {
+      conditions.push(
+         "EXISTS (SELECT 1 FROM note_tags t
+            WHERE t.note_id = n.id AND t.tag = ?)",
+      );
+      values.push(Value::Text(tag.trim_start_matches('#').to_lowercase()));
+   }
+
+   // Imported notes have no time of their own, the recording's stands in
+   if let Some(from) = query.from {
+      conditions.push("COALESCE(n.created_at, r.started_at) >= ?");
+      values.push(Value::Text(format_date(from)));
+   }
+   if let Some(to) = query.to {
+      conditions.push("COALESCE(n.created_at, r.started_at) <= ?");
+      values.push(Value::Text(format_date(to)));
+   }
+
+   if !conditions.is_empty() {
+      sql.push_str(" WHERE ");
+      sql.push_str(&conditions.join(" AND "));
+   }
+
+   sql.push_str(if text_query.is_some() {
+      " ORDER BY bm25(notes_fts)"
+   } else {
+      " ORDER BY COALESCE(n.created_at, r.started_at) DESC, n.timecode_ms"
+   });
+   sql.push_str(" LIMIT ?");
+   values.push(Value::Integer(
+      query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).into(),
+   ));
+
+   let mut statement = connection.prepare(&sql)?;
+   let matches = statement
+      .query_map(params_from_iter(values), |row| {
+         let recording_path: String = row.get(0)?;
+         let note = note_from_row(row, 5)?;
+
+         Ok((
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+            NoteSearchResult {
+               link: format!(
+                  "{}#t={:.3}",
+                  file_url(Path::new(&recording_path)),
+                  note.timecode_ms.max(0) as f64 / 1000.0
+               ),
+               recording_path,
+               recording_started_at: parse_date(row.get(1)?),
+               // bm25 is lower for better matches
+               score: row.get::<_, Option<f64>>(4)?.map(|score| -score),
+               note,
+               before: Vec::new(),
+               after: Vec::new(),
+            },
+         ))
+      })?
+      .collect::<rusqlite::Result<Vec<_>>>()?;
+
+   matches
+      .into_iter()
+      .map(|(recording_id, note_row_id, mut result)| {
+         result.before = surrounding_notes(
+            connection,
+            recording_id,
+            note_row_id,
+            &result.note,
+            true,
+         )?;
+         result.after = surrounding_notes(
+            connection,
+            recording_id,
+            note_row_id,
+            &result.note,
+            false,
+         )?;
+         Ok(result)
+      })
+      .collect()
+}
+
+/// Up to `CONTEXT_NOTES` notes of the same recording right before or after
+/// `note`, in recording order
+fn surrounding_notes(
+   connection: &Connection,
+   recording_id: i64,
+   note_row_id: i64,
+   note: &Note,
+   before: bool,
+) -> rusqlite::Result<Vec<Note>> {
+   let (comparison, order) = if before { ("<", "DESC") } else { (">", "ASC") };
+   let sql = format!(
+      "SELECT {columns} FROM notes n
+      WHERE n.recording_id = ?1 AND (n.timecode_ms, n.id) {comparison} (?2, ?3)
+      ORDER BY n.timecode_ms {order}, n.id {order}
+      LIMIT ?4",
+      columns = NOTE_COLUMNS,
+      comparison = comparison,
+      order = order
+   );
+
+   let mut notes = connection
+      .prepare(&sql)?
+      .query_map(
+         params![recording_id, note.timecode_ms, note_row_id, CONTEXT_NOTES],
+         |row| note_from_row(row, 0),
+      )?
+      .collect::<rusqlite::Result<Vec<_>>>()?;
+
+   if before {
+      notes.reverse();
+   }
+
+   Ok(notes)
+}
+
+/// Read the `NOTE_COLUMNS` starting at column `first`
+fn note_from_row(row: &Row, first: usize) -> rusqlite::Result<Note> {
+   let tags: Option<String> = row.get(first + 6)?;
+
+   Ok(Note {
+      id: row.get::<_, i64>(first)? as u64,
+      timecode_ms: row.get(first + 1)?,
+      end_timecode_ms: row.get(first + 2)?,
+      created_at: parse_date(row.get(first + 3)?),
+      scene: row.get(first + 4)?,
+      text: row.get(first + 5)?,
+      tags: tags
+         .map(|tags| tags.split(' ').map(String::from).collect())
+         .unwrap_or_default(),
+   })
+}
+
+/// Each word becomes a quoted prefix term, so user input cannot break the
+/// FTS5 query syntax
+fn fts_query(text: &str) -> String {
+   text
+      .split_whitespace()
+      .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+      .collect::<Vec<_>>()
+      .join(" ")
+}
+
+/// Fixed-width UTC so stored dates compare correctly as text
+fn format_date(date: DateTime<Utc>) -> String {
+   date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_date(date: Option<String>) -> Option<DateTime<Utc>> {
+   DateTime::parse_from_rfc3339(&date?)
+      .ok()
+      .map(|date| date.with_timezone(&Utc))
+}
@@ -7,6 +7,16 @@ use strum::{AsRefStr, Display, EnumString};
 pub enum RecordingEvents {
    #[strum(serialize = "recording:status")]
    Status,
+   #[strum(serialize = "recording:export_warnings")]
+   ExportWarnings,
+   #[strum(serialize = "recording:note_undone")]
+   NoteUndone,
+   #[strum(serialize = "recording:note_restored")]
+   NoteRestored,
+   #[strum(serialize = "recording:marker_added")]
+   MarkerAdded,
+   #[strum(serialize = "recording:no_range_open")]
+   NoRangeOpen,
 }
 
 #[derive(
@@ -31,3 +41,44 @@ pub struct RecordingStatus {
    pub active: bool,
    pub paused: bool,
 }
+
+/// A completed pause, as wall-clock milliseconds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Pause {
+   pub start: i64,
+   pub end: i64,
+}
+
+/// Frame rate of the OBS video output as a fraction, e.g. 30000/1001
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameRate {
+   pub numerator: u32,
+   pub denominator: u32,
+}
+
+impl FrameRate {
+   pub fn fps(&self) -> f64 {
+      self.numerator as f64 / self.denominator.max(1) as f64
+   }
+
+   /// Whole frames per second used when counting timecode frames
+   pub fn timebase(&self) -> u32 {
+      self.fps().round().max(1.0) as u32
+   }
+
+   /// Frame index containing the given millisecond offset
+   pub fn frames_from_ms(&self, timecode_ms: i64) -> i64 {
+      (timecode_ms.max(0) as f64 * self.fps() / 1000.0).round() as i64
+   }
+}
+
+impl Default for FrameRate {
+   fn default() -> Self {
+      Self {
+         numerator: 30,
+         denominator: 1,
+      }
+   }
+}
@@ -1,5 +1,6 @@
 use std::{sync::Mutex, time::Duration};
 
+use chrono::DateTime;
 use futures::StreamExt;
 use log::{info, warn};
 use tauri::{Emitter, Manager};
@@ -7,15 +8,39 @@ use tauri_plugin_positioner::{Position, WindowExt};
 
 use crate::{
    constants::WindowLabel,
+   note_capture::{
+      commands::{close_open_range, rewrite_note_file},
+      exporters::{
+         current_frame_rate, embed_chapters, export_notes, sibling_recording,
+         ExportContext, FFMETADATA_ID, TEXT_ID,
+      },
+      templates::{render_file_template, FileTemplateValues},
+   },
+   notes_library::service::{add_recording, spawn_refresh, RecordingEntry},
    obs_websocket_connection::models::{
-      ConnectionEvents, ConnectionStatus, RecordingEvents, RecordingStatus,
+      ConnectionEvents, ConnectionStatus, FrameRate, Pause, RecordingEvents,
+      RecordingStatus,
    },
-   state::{RecordingState, RecordingStateMutex},
+   orphaned_notes::service::{
+      keep_as_orphan, notify_orphans, temp_note_file_name,
+   },
+   state::{AppSettingsState, RecordingState, RecordingStateMutex},
    system_tray::service::{update_system_tray_icon, SystemTrayIcon},
    window_utilities::{commands::hide_window, WindowUtilitiesExt},
    GlobalState, ServerConfigState,
 };
 
+/// The duration OBS reports runs about this far behind the recording, so
+/// it is added back to get notes closer to the actual timecode
+const OBS_DURATION_LAG_MS: i64 = 1100;
+
+/// Drift between our timecode and OBS's before the pause accounting is
+/// corrected
+const RESYNC_TOLERANCE_MS: i64 = 2000;
+
+/// Recording outputs OBS creates for its simple and advanced output modes
+const RECORD_OUTPUTS: [&str; 2] = ["simple_file_output", "adv_file_output"];
+
 pub async fn websocket_connection(app_handle: tauri::AppHandle) {
    let global_state = app_handle.state::<GlobalState>();
    let mut server_config_changed_rx =
@@ -92,19 +117,41 @@ async fn handle_client_connection(
 ) {
    if let Ok(version) = client.general().version().await {
       info!("Connected to OBS Version {}", version.obs_version);
+      if let Ok(mut obs_version) =
+         app_handle.state::<GlobalState>().obs_version.lock()
+      {
+         *obs_version = Some(version.obs_version.to_string());
+      }
       connection_changed(app_handle, ConnectionStatus::Connected);
    }
 
-   if let Ok(initial_status) = client.recording().status().await {
-      update_recording_status(
-         app_handle,
-         initial_status.active,
-         initial_status.paused,
-         None,
-         Some(initial_status.duration.whole_milliseconds() as i64),
-      );
+   refresh_frame_rate(app_handle, &client).await;
+
+   match client.config().record_directory().await {
+      Ok(directory) => {
+         if let Ok(mut record_directory) =
+            app_handle.state::<GlobalState>().record_directory.lock()
+         {
+            *record_directory = Some(directory);
+         }
+      }
+      Err(err) => warn!("Failed to get OBS record directory: {}", err),
+   }
+   // Picks up notes edited by hand while we were not connected
+   spawn_refresh(app_handle);
+
+   match client.scenes().current_program_scene().await {
+      Ok(scene) => set_current_scene(app_handle, Some(scene.id.name)),
+      Err(err) => warn!("Failed to get OBS program scene: {}", err),
    }
 
+   if let Some(recording) = obs_recording(&client).await {
+      reconcile_recording_status(app_handle, &recording);
+   }
+   // A recording that stopped while we were away can leave its note file
+   // behind, so orphans are looked for once reconciled
+   notify_orphans(app_handle);
+
    let Ok(events) = client.events() else {
       return;
    };
@@ -115,6 +162,17 @@ async fn handle_client_connection(
    loop {
       tokio::select! {
           Some(event) = events.next() => {
+              // Video settings can change between recordings
+              if matches!(
+                  event,
+                  obws::events::Event::RecordStateChanged {
+                      state: obws::events::OutputState::Started,
+                      ..
+                  }
+              ) {
+                  refresh_frame_rate(app_handle, &client).await;
+              }
+
               if let Err(e) = event_handler(	event, app_handle) {
                   warn!("Event handler error: {}", e);
                   connection_changed(app_handle, ConnectionStatus::Disconnected);
@@ -130,6 +188,33 @@ async fn handle_client_connection(
    }
 }
 
+async fn refresh_frame_rate(
+   app_handle: &tauri::AppHandle,
+   client: &obws::Client,
+) {
+   match client.config().video_settings().await {
+      Ok(settings) => {
+         if let Ok(mut frame_rate) =
+            app_handle.state::<GlobalState>().frame_rate.lock()
+         {
+            *frame_rate = Some(FrameRate {
+               numerator: settings.fps_numerator,
+               denominator: settings.fps_denominator,
+            });
+         }
+      }
+      Err(err) => warn!("Failed to get OBS video settings: {}", err),
+   }
+}
+
+fn set_current_scene(app_handle: &tauri::AppHandle, scene: Option<String>) {
+   if let Ok(mut current_scene) =
+      app_handle.state::<GlobalState>().current_scene.lock()
+   {
+      *current_scene = scene;
+   }
+}
+
 fn event_handler(
    event: obws::events::Event,
    app_handle: &tauri::AppHandle,
@@ -139,6 +224,23 @@ fn event_handler(
       Event::ServerStopped => {
          Err("OBS WebSocket server has stopped.".to_string())
       }
+      Event::CurrentProgramSceneChanged { id } => {
+         set_current_scene(app_handle, Some(id.name));
+         Ok(())
+      }
+      Event::RecordFileChanged { path } => {
+         info!("Recording split, now writing to {}", path);
+
+         // The notes stay with the first file, the new one is only
+         // followed so a reconnect can tell a split from a new recording
+         if let Ok(mut state) = app_handle.state::<RecordingStateMutex>().lock()
+         {
+            state.output_path = Some(path);
+            state.save(app_handle);
+         }
+
+         Ok(())
+      }
       Event::RecordStateChanged { state, path, .. } => {
          info!("Recording state changed: {:?}, path: {:?}", state, path);
 
@@ -223,17 +325,24 @@ fn update_recording_status(
          was_active,
          path,
          if let Some(duration) = existing_duration_ms {
-            // Negative 1.1 second as the duration is not accurate from OBS
-            // this gets notes closer to actual timecode
-            now - duration - 1100
+            now - duration - OBS_DURATION_LAG_MS
          } else {
             now
          },
       );
       handle_pause_state(&mut state, active, paused, was_paused, now);
 
+      // A recording carried over from before a restart or reconnect may
+      // have been paused while we were not listening
+      if let (true, true, Some(duration)) =
+         (active, was_active, existing_duration_ms)
+      {
+         resync_paused_time(&mut state, duration, now);
+      }
+
       state.recording_status.active = active;
       state.recording_status.paused = paused;
+      state.save(app_handle);
    } else {
       warn!("Failed to lock recording_status mutex");
    }
@@ -241,6 +350,279 @@ fn update_recording_status(
    emit_recording_status_change(app_handle, active, paused);
 }
 
+/// What OBS reports about its recording when we connect
+struct ObsRecording {
+   status: obws::responses::recording::RecordStatus,
+   /// File the recording output is writing to
+   path: Option<String>,
+   /// Automatic file splitting is on, so the file can change mid-recording
+   splits_files: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct FileOutputSettings {
+   path: Option<String>,
+}
+
+async fn obs_recording(client: &obws::Client) -> Option<ObsRecording> {
+   let status = client.recording().status().await.ok()?;
+   let path = if status.active {
+      record_output_path(client).await
+   } else {
+      None
+   };
+   let splits_files = client
+      .profiles()
+      .parameter("AdvOut", "RecSplitFile")
+      .await
+      .ok()
+      .and_then(|parameter| parameter.value)
+      .is_some_and(|value| value == "true");
+
+   Some(ObsRecording {
+      status,
+      path,
+      splits_files,
+   })
+}
+
+/// File the active recording output writes to, as OBS itself reports it
+async fn record_output_path(client: &obws::Client) -> Option<String> {
+   let outputs = client.outputs().list().await.ok()?;
+   let output = outputs.iter().find(|output| {
+      output.active && RECORD_OUTPUTS.contains(&output.name.as_str())
+   })?;
+
+   client
+      .outputs()
+      .settings::<FileOutputSettings>(&output.name)
+      .await
+      .ok()?
+      .path
+}
+
+/// What became of the recording we were tracking, restored from disk or
+/// carried over a dropped connection, while we were not listening
+#[derive(Debug)]
+enum Reconciliation {
+   /// Nothing was being tracked, OBS's status is taken as is
+   Untracked,
+   Continued,
+   StoppedWhileAway,
+   /// The tracked recording stopped and OBS started another, writing to
+   /// this file if it said
+   ReplacedWhileAway(Option<String>),
+}
+
+/// The recording we were tracking, as last saved
+struct TrackedRecording {
+   recording_path: Option<String>,
+   output_path: Option<String>,
+   /// Recorded time the notes reach, which OBS's duration cannot be
+   /// shorter than if it is still the same recording
+   noted_ms: Option<i64>,
+   /// Recorded time we counted up to now, excluding pauses we saw
+   elapsed_ms: i64,
+}
+
+/// Bring the recording state in line with OBS after connecting. Missed
+/// stops are finalized as if we had seen them, and a recording that
+/// carried on keeps its start so note timecodes do not shift.
+fn reconcile_recording_status(
+   app_handle: &tauri::AppHandle,
+   obs: &ObsRecording,
+) {
+   let now = chrono::Utc::now().timestamp_millis();
+   let duration_ms = obs.status.duration.whole_milliseconds() as i64;
+
+   let tracked = app_handle
+      .state::<RecordingStateMutex>()
+      .lock()
+      .ok()
+      .filter(|state| {
+         state.recording_status.active && state.recording_start.is_some()
+      })
+      .map(|state| TrackedRecording {
+         recording_path: tracked_recording_path(&state),
+         output_path: state.output_path.clone(),
+         noted_ms: state
+            .notes
+            .iter()
+            .map(|note| note.end_timecode_ms.unwrap_or(note.timecode_ms))
+            .max(),
+         elapsed_ms: state.elapsed_ms(now).unwrap_or_default(),
+      });
+
+   let reconciliation = reconcile(tracked.as_ref(), obs, duration_ms);
+   info!("Reconciling recording state with OBS: {:?}", reconciliation);
+
+   // The recording stopped growing when OBS stopped it
+   let tracked_stopped_at = tracked
+      .as_ref()
+      .and_then(|tracked| tracked.recording_path.as_deref())
+      .and_then(modified_ms);
+
+   match reconciliation {
+      Reconciliation::Untracked => {
+         update_recording_status(
+            app_handle,
+            obs.status.active,
+            obs.status.paused,
+            obs.path.clone(),
+            Some(duration_ms),
+         );
+      }
+      Reconciliation::Continued => {
+         if let Ok(mut state) = app_handle.state::<RecordingStateMutex>().lock()
+         {
+            state.output_path = obs.path.clone().or(state.output_path.take());
+         }
+         update_recording_status(
+            app_handle,
+            obs.status.active,
+            obs.status.paused,
+            None,
+            Some(duration_ms),
+         );
+         // Restored state is already active, so starting never ran to
+         // bring up the overlay
+         show_recording_status_window(app_handle);
+      }
+      Reconciliation::StoppedWhileAway => {
+         finish_recording_while_away(
+            app_handle,
+            tracked_stopped_at.unwrap_or(now),
+         );
+      }
+      Reconciliation::ReplacedWhileAway(newer) => {
+         let new_start = now - duration_ms - OBS_DURATION_LAG_MS;
+         finish_recording_while_away(
+            app_handle,
+            tracked_stopped_at.unwrap_or(new_start).min(new_start),
+         );
+         update_recording_status(
+            app_handle,
+            obs.status.active,
+            obs.status.paused,
+            newer,
+            Some(duration_ms),
+         );
+      }
+   }
+}
+
+/// What became of the tracked recording, given what OBS reports now and
+/// the recorded `duration_ms` it gives
+fn reconcile(
+   tracked: Option<&TrackedRecording>,
+   obs: &ObsRecording,
+   duration_ms: i64,
+) -> Reconciliation {
+   match tracked {
+      None => Reconciliation::Untracked,
+      Some(_) if !obs.status.active => Reconciliation::StoppedWhileAway,
+      Some(tracked) if is_new_recording(tracked, obs, duration_ms) => {
+         Reconciliation::ReplacedWhileAway(obs.path.clone())
+      }
+      Some(_) => Reconciliation::Continued,
+   }
+}
+
+/// Whether the recording OBS reports is another one than we tracked. Only
+/// a split moves a recording on to a new file, and OBS counts its
+/// duration from the first file, so a split recording cannot be shorter
+/// than the notes already taken in it.
+fn is_new_recording(
+   tracked: &TrackedRecording,
+   obs: &ObsRecording,
+   duration_ms: i64,
+) -> bool {
+   let shorter_than =
+      |ms: i64| duration_ms + OBS_DURATION_LAG_MS + RESYNC_TOLERANCE_MS < ms;
+
+   match (tracked.output_path.as_deref(), obs.path.as_deref()) {
+      (Some(output_path), Some(path)) if output_path == path => false,
+      (Some(_), Some(_)) if !obs.splits_files => true,
+      // With neither a file nor notes to go by, a duration well short of
+      // the time we counted gives a new recording away. Missed pauses
+      // look the same, but without notes there is nothing to misplace.
+      (None, _) if tracked.noted_ms.is_none() => {
+         shorter_than(tracked.elapsed_ms)
+      }
+      // Either a split, or a recording OBS gave no path for
+      _ => tracked.noted_ms.is_some_and(shorter_than),
+   }
+}
+
+/// Run the stop we missed, at the time the recording actually stopped
+fn finish_recording_while_away(app_handle: &tauri::AppHandle, stopped_at: i64) {
+   if let Ok(mut state) = app_handle.state::<RecordingStateMutex>().lock() {
+      let recording_path = tracked_recording_path(&state)
+         .filter(|path| std::path::Path::new(path).is_file());
+      state.recording_path = None;
+
+      // A recording moved or renamed since leaves its note file behind,
+      // which is moved to be offered as an orphan
+      if recording_path.is_none() {
+         keep_note_file_as_orphan(&mut state, app_handle);
+      }
+
+      if let Err(err) =
+         stop_recording(&mut state, app_handle, recording_path, stopped_at)
+      {
+         warn!("Failed to finalize notes: {}", err);
+      }
+
+      state.recording_status = RecordingStatus::default();
+      state.save(app_handle);
+   } else {
+      warn!("Failed to lock recording_status mutex");
+   }
+
+   emit_recording_status_change(app_handle, false, false);
+}
+
+/// Recording the notes belong to, found by the shared file name for state
+/// saved before the path was kept
+fn tracked_recording_path(state: &RecordingState) -> Option<String> {
+   state.recording_path.clone().or_else(|| {
+      let note_file_path = std::path::Path::new(state.note_file_path.as_ref()?);
+
+      sibling_recording(note_file_path)
+         .map(|path| path.to_string_lossy().to_string())
+   })
+}
+
+fn keep_note_file_as_orphan(
+   state: &mut RecordingState,
+   app_handle: &tauri::AppHandle,
+) {
+   let (Some(note_path), Some(recording_start)) =
+      (state.note_file_path.clone(), state.recording_start)
+   else {
+      return;
+   };
+   if !std::path::Path::new(&note_path).is_file() {
+      return;
+   }
+
+   match keep_as_orphan(
+      app_handle,
+      std::path::Path::new(&note_path),
+      recording_start,
+   ) {
+      Ok(orphan_path) => {
+         state.note_file_path = Some(orphan_path.to_string_lossy().to_string())
+      }
+      Err(err) => warn!("Failed to keep note file {}: {}", note_path, err),
+   }
+}
+
+fn modified_ms(path: &str) -> Option<i64> {
+   let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+   Some(DateTime::<chrono::Utc>::from(modified).timestamp_millis())
+}
+
 fn handle_recording_lifecycle(
    state: &mut RecordingState,
    app_handle: &tauri::AppHandle,
@@ -257,7 +639,7 @@ fn handle_recording_lifecycle(
          }
       }
    } else if !active && was_active {
-      match stop_recording(state, app_handle, path) {
+      match stop_recording(state, app_handle, path, now) {
          Ok(_) => {}
          Err(err) => {
             warn!("Failed to finalize notes: {}", err);
@@ -275,48 +657,127 @@ fn start_recording(
    state.recording_start = Some(now);
    state.accumulated_pause_duration = 0;
    state.pause_start = None;
-   state.note_file_path = Some(resolve_note_file_path(app_handle, path, now)?);
+   state.pauses.clear();
+   state.notes.clear();
+   state.next_note_id = 0;
+   state.note_file_header.clear();
+   state.undone_note = None;
+   state.open_range_id = None;
+   state.recording_path = path.clone();
+   state.output_path = path.clone();
+
+   let recording = path.as_deref().map(file_name);
+   let note_path = resolve_note_file_path(app_handle, path, now)?;
+   if let Some(settings) = current_app_settings(app_handle) {
+      let header = render_file_template(
+         &settings.note_header_template,
+         &FileTemplateValues {
+            recording: &recording.unwrap_or_else(|| file_name(&note_path)),
+            started_at: DateTime::from_timestamp_millis(now),
+            obs_version: current_obs_version(app_handle).as_deref(),
+            note_count: 0,
+            duration_ms: None,
+         },
+      );
 
+      if let Err(err) = append_to_note_file(&note_path, &header) {
+         warn!("Failed to write note file header: {}", err);
+      }
+      state.note_file_header = header;
+   }
+   state.note_file_path = Some(note_path);
+
+   show_recording_status_window(app_handle);
+
+   Ok(())
+}
+
+fn show_recording_status_window(app_handle: &tauri::AppHandle) {
    let recording_status_win = app_handle
       .get_webview_window(WindowLabel::RecordingStatus.as_ref())
       .expect("Failed to get recording status window");
    let _ = recording_status_win.move_window(Position::BottomLeft);
    recording_status_win.position_above_taskbar();
    let _ = recording_status_win.show();
-
-   Ok(())
 }
 
 fn stop_recording(
    state: &mut RecordingState,
    app_handle: &tauri::AppHandle,
    output_file_path: Option<String>,
+   now: i64,
 ) -> Result<(), std::io::Error> {
+   let recording_duration_ms = state.elapsed_ms(now);
+   // Close a pause still open when the recording stopped
+   resume_recording(state, now);
+   // and a range never marked out, which ends with the recording
+   if let Some(end) = recording_duration_ms {
+      if close_open_range(state, end, "").is_some() {
+         rewrite_closed_range(state, app_handle);
+      }
+   }
+   let finished = state.clone();
+   // Notes belong to the first file of a recording OBS split, not the
+   // last one it reports stopping
+   let output_file_path = state.recording_path.take().or(output_file_path);
+   state.output_path = None;
+
    state.recording_start = None;
    state.accumulated_pause_duration = 0;
    state.pause_start = None;
+   state.pauses.clear();
+   state.notes.clear();
+   state.next_note_id = 0;
+   state.note_file_header.clear();
+   state.undone_note = None;
+   state.open_range_id = None;
 
    if let Some(note_path) = state.note_file_path.take() {
       if let Some(output_path) = output_file_path {
-         let metadata = std::fs::metadata(&note_path);
-         if metadata.is_err() || metadata.unwrap().len() == 0 {
+         if finished.notes.is_empty() {
             std::fs::remove_file(&note_path)?;
             return Ok(());
          }
 
-         let final_note_path = std::path::Path::new(&output_path)
-            .with_extension("txt")
-            .to_string_lossy()
-            .to_string();
+         if let Some(settings) = current_app_settings(app_handle) {
+            let footer = render_file_template(
+               &settings.note_footer_template,
+               &FileTemplateValues {
+                  recording: &file_name(&output_path),
+                  started_at: finished
+                     .recording_start
+                     .and_then(DateTime::from_timestamp_millis),
+                  obs_version: current_obs_version(app_handle).as_deref(),
+                  note_count: finished.notes.len(),
+                  duration_ms: recording_duration_ms,
+               },
+            );
 
-         if note_path != final_note_path {
-            if let Some(parent) =
-               std::path::Path::new(&final_note_path).parent()
-            {
-               std::fs::create_dir_all(parent)?;
+            if let Err(err) = append_to_note_file(&note_path, &footer) {
+               warn!("Failed to write note file footer: {}", err);
             }
+         }
+
+         export_note_sidecars(
+            app_handle,
+            &output_path,
+            &note_path,
+            &finished,
+            recording_duration_ms,
+         );
 
-            std::fs::rename(&note_path, &final_note_path)?;
+         let obs_version = current_obs_version(app_handle);
+         let entry = RecordingEntry {
+            path: &output_path,
+            started_at: finished
+               .recording_start
+               .and_then(DateTime::from_timestamp_millis),
+            duration_ms: recording_duration_ms,
+            obs_version: obs_version.as_deref(),
+            notes: &finished.notes,
+         };
+         if let Err(err) = add_recording(app_handle, &entry) {
+            warn!("Failed to add recording to notes library: {}", err);
          }
       }
    }
@@ -329,6 +790,136 @@ fn stop_recording(
    Ok(())
 }
 
+/// Run every enabled exporter, the note file itself is moved next to the
+/// recording by the text exporter or removed when that is disabled
+fn export_note_sidecars(
+   app_handle: &tauri::AppHandle,
+   output_path: &str,
+   note_path: &str,
+   finished: &RecordingState,
+   recording_duration_ms: Option<i64>,
+) {
+   let Some(settings) = current_app_settings(app_handle) else {
+      warn!("Failed to lock app settings, skipping note export");
+      return;
+   };
+
+   let obs_version = current_obs_version(app_handle);
+
+   let summary = export_notes(
+      &ExportContext {
+         output_path: std::path::Path::new(output_path),
+         note_file_path: std::path::Path::new(note_path),
+         notes: &finished.notes,
+         settings: &settings,
+         recording_duration_ms,
+         recording_start: finished.recording_start,
+         pauses: &finished.pauses,
+         paused_duration_ms: finished.accumulated_pause_duration,
+         obs_version: obs_version.as_deref(),
+         frame_rate: current_frame_rate(app_handle),
+      },
+      &settings.enabled_exporters,
+   );
+
+   let is_enabled =
+      |id: &str| settings.enabled_exporters.iter().any(|e| e == id);
+
+   // The note file is the only copy of the notes until another exporter
+   // has written all of them, so it is kept unless one has and none failed
+   if !is_enabled(TEXT_ID) && summary.has_every_note && summary.failed == 0 {
+      if let Err(err) = std::fs::remove_file(note_path) {
+         warn!("Failed to remove note file {}: {}", note_path, err);
+      }
+   }
+
+   if !summary.warnings.is_empty() {
+      let _ = app_handle
+         .emit(RecordingEvents::ExportWarnings.as_ref(), summary.warnings);
+   }
+
+   // Only a metadata file written for this recording is embedded
+   if let Some(ffmpeg_path) = settings.ffmpeg_path.clone() {
+      if summary.written.contains(&FFMETADATA_ID) {
+         spawn_embed_chapters(app_handle, ffmpeg_path, output_path);
+      }
+   }
+}
+
+/// Remuxing copies the whole recording, so it runs off the event loop
+fn spawn_embed_chapters(
+   app_handle: &tauri::AppHandle,
+   ffmpeg_path: String,
+   output_path: &str,
+) {
+   let app_handle = app_handle.clone();
+   let output_path = output_path.to_string();
+
+   tauri::async_runtime::spawn_blocking(move || {
+      if let Err(err) = embed_chapters(
+         std::path::Path::new(&ffmpeg_path),
+         std::path::Path::new(&output_path),
+      ) {
+         warn!("Failed to embed chapters in {}: {}", output_path, err);
+         let _ = app_handle.emit(
+            RecordingEvents::ExportWarnings.as_ref(),
+            vec![format!("Failed to embed chapters: {}", err)],
+         );
+      }
+   });
+}
+
+fn current_app_settings(
+   app_handle: &tauri::AppHandle,
+) -> Option<AppSettingsState> {
+   app_handle
+      .state::<Mutex<AppSettingsState>>()
+      .lock()
+      .map(|s| s.clone())
+      .ok()
+}
+
+fn current_obs_version(app_handle: &tauri::AppHandle) -> Option<String> {
+   app_handle
+      .state::<GlobalState>()
+      .obs_version
+      .lock()
+      .ok()
+      .and_then(|v| v.clone())
+}
+
+fn file_name(path: &str) -> String {
+   std::path::Path::new(path)
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| path.to_string())
+}
+
+fn rewrite_closed_range(state: &RecordingState, app_handle: &tauri::AppHandle) {
+   let Some(settings) = current_app_settings(app_handle) else {
+      return;
+   };
+
+   if let Err(err) = rewrite_note_file(state, &settings.note_line_template) {
+      warn!("Failed to rewrite note file: {}", err);
+   }
+}
+
+/// Append a rendered header or footer to the note file
+fn append_to_note_file(
+   note_path: &str,
+   contents: &str,
+) -> Result<(), std::io::Error> {
+   use std::io::Write;
+
+   if contents.is_empty() {
+      return Ok(());
+   }
+
+   let mut file = std::fs::OpenOptions::new().append(true).open(note_path)?;
+   writeln!(file, "{}", contents)
+}
+
 fn resolve_note_file_path(
    app_handle: &tauri::AppHandle,
    path: Option<String>,
@@ -341,12 +932,12 @@ fn resolve_note_file_path(
          .to_string()
    } else if let Ok(cache_dir) = app_handle.path().cache_dir() {
       cache_dir
-         .join(format!("temp_recording_{}.txt", now))
+         .join(temp_note_file_name(now))
          .to_string_lossy()
          .to_string()
    } else {
       warn!("Failed to get cache directory for note file path");
-      format!("temp_recording_{}.txt", now)
+      temp_note_file_name(now)
    };
 
    if let Some(parent) = std::path::Path::new(&file_path).parent() {
@@ -376,6 +967,49 @@ fn handle_pause_state(
    }
 }
 
+/// Treat any difference between our elapsed time and the recorded
+/// duration OBS reports, which leaves out paused time, as missed pauses.
+/// A missed pause is recorded as ending now, since when it happened is
+/// unknown, so exporters still see every pause that was subtracted.
+fn resync_paused_time(state: &mut RecordingState, duration_ms: i64, now: i64) {
+   let Some(elapsed) = state.elapsed_ms(now) else {
+      return;
+   };
+
+   let drift = elapsed - duration_ms - OBS_DURATION_LAG_MS;
+   if drift.abs() <= RESYNC_TOLERANCE_MS {
+      return;
+   }
+
+   info!("Recording timecode off by {}ms, resyncing with OBS", drift);
+   if drift > 0 {
+      let recording_start = state.recording_start.unwrap_or(now - drift);
+      state.pauses.push(Pause {
+         start: (now - drift).max(recording_start),
+         end: now,
+      });
+      state.accumulated_pause_duration += drift;
+   } else {
+      // We counted pauses OBS did not, so drop the latest of them
+      let mut excess = -drift;
+      while excess > 0 {
+         let Some(pause) = state.pauses.last_mut() else {
+            break;
+         };
+         let length = pause.end - pause.start;
+         if length > excess {
+            pause.end -= excess;
+            excess = 0;
+         } else {
+            excess -= length;
+            state.pauses.pop();
+         }
+      }
+      state.accumulated_pause_duration =
+         (state.accumulated_pause_duration + drift).max(0);
+   }
+}
+
 fn pause_recording(state: &mut RecordingState, now: i64) {
    state.pause_start = Some(now);
 }
@@ -384,6 +1018,10 @@ fn resume_recording(state: &mut RecordingState, now: i64) {
    if let Some(pause_start) = state.pause_start {
       let pause_duration = now - pause_start;
       state.accumulated_pause_duration += pause_duration;
+      state.pauses.push(Pause {
+         start: pause_start,
+         end: now,
+      });
    }
 
    state.pause_start = None;
@@ -399,3 +1037,107 @@ fn emit_recording_status_change(
       RecordingStatus { active, paused },
    );
 }
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn tracked(
+      output_path: Option<&str>,
+      noted_ms: Option<i64>,
+      elapsed_ms: i64,
+   ) -> TrackedRecording {
+      TrackedRecording {
+         recording_path: output_path.map(String::from),
+         output_path: output_path.map(String::from),
+         noted_ms,
+         elapsed_ms,
+      }
+   }
+
+   fn obs(
+      active: bool,
+      path: Option<&str>,
+      splits_files: bool,
+   ) -> ObsRecording {
+      ObsRecording {
+         status: obws::responses::recording::RecordStatus {
+            active,
+            ..Default::default()
+         },
+         path: path.map(String::from),
+         splits_files,
+      }
+   }
+
+   #[test]
+   fn untracked_without_a_restored_recording() {
+      let reconciliation = reconcile(None, &obs(true, Some("a.mkv"), false), 0);
+      assert!(matches!(reconciliation, Reconciliation::Untracked));
+   }
+
+   #[test]
+   fn continued_on_the_same_file() {
+      let tracked = tracked(Some("a.mkv"), Some(60_000), 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, Some("a.mkv"), false), 5_000);
+      assert!(matches!(reconciliation, Reconciliation::Continued));
+   }
+
+   #[test]
+   fn continued_across_a_split() {
+      let tracked = tracked(Some("a.mkv"), Some(60_000), 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, Some("b.mkv"), true), 119_000);
+      assert!(matches!(reconciliation, Reconciliation::Continued));
+   }
+
+   #[test]
+   fn continued_without_a_path_when_the_duration_matches() {
+      let tracked = tracked(None, None, 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, None, false), 118_900);
+      assert!(matches!(reconciliation, Reconciliation::Continued));
+   }
+
+   #[test]
+   fn stopped_while_away() {
+      let tracked = tracked(Some("a.mkv"), Some(60_000), 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(false, None, false), 0);
+      assert!(matches!(reconciliation, Reconciliation::StoppedWhileAway));
+   }
+
+   #[test]
+   fn replaced_by_another_file() {
+      let tracked = tracked(Some("a.mkv"), Some(60_000), 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, Some("b.mkv"), false), 119_000);
+      assert!(matches!(
+         reconciliation,
+         Reconciliation::ReplacedWhileAway(Some(path)) if path == "b.mkv"
+      ));
+   }
+
+   #[test]
+   fn replaced_when_split_file_is_shorter_than_the_notes() {
+      let tracked = tracked(Some("a.mkv"), Some(60_000), 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, Some("b.mkv"), true), 10_000);
+      assert!(matches!(
+         reconciliation,
+         Reconciliation::ReplacedWhileAway(Some(_))
+      ));
+   }
+
+   #[test]
+   fn replaced_without_a_path_or_notes_when_the_duration_is_short() {
+      let tracked = tracked(None, None, 120_000);
+      let reconciliation =
+         reconcile(Some(&tracked), &obs(true, None, false), 10_000);
+      assert!(matches!(
+         reconciliation,
+         Reconciliation::ReplacedWhileAway(None)
+      ));
+   }
+}
@@ -0,0 +1,36 @@
+use crate::orphaned_notes::{models::OrphanedNoteFile, service};
+
+/// Note files left behind by recordings that never finished, including
+/// archived ones that were never attached
+#[tauri::command]
+pub async fn list_orphaned_notes(
+   app_handle: tauri::AppHandle,
+) -> Result<Vec<OrphanedNoteFile>, String> {
+   service::find_orphans(&app_handle)
+      .map_err(|e| format!("Failed to list orphaned notes: {}", e))
+}
+
+/// Attach an orphan to a recording, returning export warnings and lines
+/// that could not be read
+#[tauri::command]
+pub async fn attach_orphaned_notes(
+   app_handle: tauri::AppHandle,
+   path: String,
+   recording_path: String,
+) -> Result<Vec<String>, String> {
+   service::attach(
+      &app_handle,
+      std::path::Path::new(&path),
+      std::path::Path::new(&recording_path),
+   )
+   .map_err(|e| format!("Failed to attach notes: {}", e))
+}
+
+#[tauri::command]
+pub async fn archive_orphaned_notes(
+   app_handle: tauri::AppHandle,
+   path: String,
+) -> Result<(), String> {
+   service::archive(&app_handle, std::path::Path::new(&path))
+      .map_err(|e| format!("Failed to archive notes: {}", e))
+}
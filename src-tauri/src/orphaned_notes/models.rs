@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use strum::{AsRefStr, Display, EnumString};
+
+#[derive(
+   EnumString, AsRefStr, Display, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+pub enum OrphanedNotesEvents {
+   #[strum(serialize = "orphaned_notes:found")]
+   Found,
+}
+
+/// Temporary note file left behind by a recording that never finished
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedNoteFile {
+   pub path: String,
+   /// Recording start, taken from the file name
+   pub started_at: Option<DateTime<Utc>>,
+   pub note_count: usize,
+   /// Lines that could not be read as notes, kept as they were typed
+   pub unreadable_line_count: usize,
+   /// Recording in the OBS record directory that started at the same time
+   pub recording_path: Option<String>,
+   /// Moved out of the cache directory by the user, kept for later
+   pub archived: bool,
+}
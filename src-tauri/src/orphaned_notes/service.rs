@@ -0,0 +1,408 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use tauri::{Emitter, Manager};
+
+use crate::{
+   note_capture::{
+      exporters::{export_notes, ExportContext, RECORDING_EXTENSIONS, TEXT_ID},
+      parser::{parse_notes, NoteFileTemplates, ParsedNotes},
+   },
+   notes_library::service::{add_recording, RecordingEntry},
+   orphaned_notes::models::{OrphanedNoteFile, OrphanedNotesEvents},
+   state::{AppSettingsState, RecordingStateMutex},
+   GlobalState,
+};
+
+const TEMP_NOTE_PREFIX: &str = "temp_recording_";
+
+/// Folder in the app data directory holding archived orphans
+const ARCHIVE_DIR: &str = "orphaned-notes";
+
+/// How far apart a recording's creation time and the note file timestamp
+/// can be. OBS creates the file a moment after reporting the start, and
+/// the start is estimated when connecting mid-recording.
+const MATCH_TOLERANCE_MS: i64 = 60_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrphanError {
+   #[error("Failed to find the app directories: {0}")]
+   Dirs(#[from] tauri::Error),
+
+   #[error(transparent)]
+   Io(#[from] std::io::Error),
+
+   #[error("Not an orphaned note file: {0}")]
+   NotOrphaned(String),
+
+   #[error("Recording not found: {0}")]
+   RecordingNotFound(String),
+
+   #[error("The recording already has a note file: {0}")]
+   NoteFileExists(String),
+
+   #[error("Failed to lock app settings")]
+   Settings,
+}
+
+/// Name of the note file for a recording OBS gave no path for, `now` being
+/// the recording start
+pub fn temp_note_file_name(now: i64) -> String {
+   format!("{}{}.txt", TEMP_NOTE_PREFIX, now)
+}
+
+/// Move the note file of a recording that can no longer be found into the
+/// cache directory under a temporary name, so it is listed as an orphan
+/// instead of sitting unnoticed where the recording used to be
+pub fn keep_as_orphan(
+   app_handle: &tauri::AppHandle,
+   note_file_path: &Path,
+   recording_start: i64,
+) -> Result<PathBuf, OrphanError> {
+   if temp_note_timestamp(note_file_path).is_some() {
+      return Ok(note_file_path.to_path_buf());
+   }
+
+   let cache_dir = app_handle.path().cache_dir()?;
+   std::fs::create_dir_all(&cache_dir)?;
+   let orphan_path = cache_dir.join(temp_note_file_name(recording_start));
+   move_file(note_file_path, &orphan_path)?;
+
+   Ok(orphan_path)
+}
+
+/// Orphans waiting in the cache directory followed by archived ones, each
+/// with the recording it most likely belongs to. Cache orphans holding
+/// nothing but the header and footer are left out, they are removed on
+/// connect.
+pub fn find_orphans(
+   app_handle: &tauri::AppHandle,
+) -> Result<Vec<OrphanedNoteFile>, OrphanError> {
+   let active_path = app_handle
+      .state::<RecordingStateMutex>()
+      .lock()
+      .ok()
+      .and_then(|state| state.note_file_path.clone());
+   let recordings = record_directory(app_handle)
+      .map(|directory| unclaimed_recordings(&directory))
+      .unwrap_or_default();
+   let settings = current_settings(app_handle)?;
+   let templates = NoteFileTemplates::from_settings(&settings);
+
+   let mut orphans = Vec::new();
+   for (directory, archived) in [
+      (app_handle.path().cache_dir()?, false),
+      (archive_dir(app_handle)?, true),
+   ] {
+      for path in temp_note_files(&directory) {
+         let path_str = path.to_string_lossy().to_string();
+         if active_path.as_deref() == Some(path_str.as_str()) {
+            continue;
+         }
+
+         let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+         };
+         let parsed = parse_notes(&contents, &templates);
+         if is_blank(&parsed) && !archived {
+            continue;
+         }
+
+         let started_at_ms = temp_note_timestamp(&path);
+         orphans.push(OrphanedNoteFile {
+            path: path_str,
+            started_at: started_at_ms.and_then(DateTime::from_timestamp_millis),
+            note_count: parsed.notes.len(),
+            unreadable_line_count: parsed.errors.len(),
+            recording_path: started_at_ms.and_then(|started_at| {
+               closest_recording(&recordings, started_at)
+            }),
+            archived,
+         });
+      }
+   }
+
+   Ok(orphans)
+}
+
+/// Let the UI offer to attach or archive orphans, called once OBS has told
+/// us where it records to
+pub fn notify_orphans(app_handle: &tauri::AppHandle) {
+   remove_blank_orphans(app_handle);
+
+   match find_orphans(app_handle) {
+      Ok(orphans) => {
+         let pending: Vec<_> = orphans
+            .into_iter()
+            .filter(|orphan| !orphan.archived)
+            .collect();
+         if !pending.is_empty() {
+            let _ =
+               app_handle.emit(OrphanedNotesEvents::Found.as_ref(), pending);
+         }
+      }
+      Err(err) => warn!("Failed to look for orphaned note files: {}", err),
+   }
+}
+
+/// Remove cache orphans holding nothing but the header and footer, as a
+/// stopped recording would have done. Anything else typed into them is
+/// kept for the user to look at. The recording state stays locked so a
+/// recording starting meanwhile cannot lose its fresh note file.
+fn remove_blank_orphans(app_handle: &tauri::AppHandle) {
+   let (Ok(settings), Ok(cache_dir)) =
+      (current_settings(app_handle), app_handle.path().cache_dir())
+   else {
+      return;
+   };
+   let templates = NoteFileTemplates::from_settings(&settings);
+
+   let recording_state = app_handle.state::<RecordingStateMutex>();
+   let Ok(state) = recording_state.lock() else {
+      return;
+   };
+
+   for path in temp_note_files(&cache_dir) {
+      let path_str = path.to_string_lossy().to_string();
+      if state.note_file_path.as_deref() == Some(path_str.as_str()) {
+         continue;
+      }
+
+      let Ok(contents) = std::fs::read_to_string(&path) else {
+         continue;
+      };
+      if is_blank(&parse_notes(&contents, &templates)) {
+         if let Err(err) = std::fs::remove_file(&path) {
+            warn!("Failed to remove empty note file {}: {}", path_str, err);
+         }
+      }
+   }
+}
+
+fn is_blank(parsed: &ParsedNotes) -> bool {
+   parsed.notes.is_empty() && parsed.errors.is_empty()
+}
+
+/// Move an orphan next to its recording and export it as if the recording
+/// had stopped normally, returning export warnings. An orphan without any
+/// readable notes is only moved, so its lines are not lost.
+pub fn attach(
+   app_handle: &tauri::AppHandle,
+   orphan_path: &Path,
+   recording_path: &Path,
+) -> Result<Vec<String>, OrphanError> {
+   ensure_orphan(app_handle, orphan_path)?;
+
+   if !recording_path.is_file() {
+      return Err(OrphanError::RecordingNotFound(
+         recording_path.display().to_string(),
+      ));
+   }
+
+   let note_file_path = recording_path.with_extension("txt");
+   if note_file_path.exists() {
+      return Err(OrphanError::NoteFileExists(
+         note_file_path.display().to_string(),
+      ));
+   }
+
+   let settings = current_settings(app_handle)?;
+   let parsed = parse_notes(
+      &std::fs::read_to_string(orphan_path)?,
+      &NoteFileTemplates::from_settings(&settings),
+   );
+   move_file(orphan_path, &note_file_path)?;
+
+   let unreadable = parsed.errors.iter().map(|err| err.to_string());
+   if parsed.notes.is_empty() {
+      return Ok(unreadable.collect());
+   }
+
+   let recording_start = temp_note_timestamp(orphan_path);
+
+   // The note file has already been moved into place
+   let exporter_ids: Vec<String> = settings
+      .enabled_exporters
+      .iter()
+      .filter(|id| *id != TEXT_ID)
+      .cloned()
+      .collect();
+
+   let summary = export_notes(
+      &ExportContext {
+         recording_start,
+         ..ExportContext::for_note_file(
+            app_handle,
+            recording_path,
+            &note_file_path,
+            &parsed.notes,
+            &settings,
+         )
+      },
+      &exporter_ids,
+   );
+
+   let recording_path = recording_path.to_string_lossy();
+   if let Err(err) = add_recording(
+      app_handle,
+      &RecordingEntry {
+         path: &recording_path,
+         started_at: recording_start.and_then(DateTime::from_timestamp_millis),
+         duration_ms: None,
+         obs_version: None,
+         notes: &parsed.notes,
+      },
+   ) {
+      warn!("Failed to add recording to notes library: {}", err);
+   }
+
+   Ok(unreadable.chain(summary.warnings).collect())
+}
+
+/// Move an orphan out of the cache directory, where the OS may clear it,
+/// into the archive where it stays listed
+pub fn archive(
+   app_handle: &tauri::AppHandle,
+   orphan_path: &Path,
+) -> Result<(), OrphanError> {
+   ensure_orphan(app_handle, orphan_path)?;
+
+   let archive_dir = archive_dir(app_handle)?;
+   if orphan_path.parent() == Some(archive_dir.as_path()) {
+      return Ok(());
+   }
+
+   std::fs::create_dir_all(&archive_dir)?;
+   let file_name = orphan_path.file_name().ok_or_else(|| {
+      OrphanError::NotOrphaned(orphan_path.display().to_string())
+   })?;
+   move_file(orphan_path, &archive_dir.join(file_name))?;
+
+   Ok(())
+}
+
+/// Only temporary note files Clip Mark wrote, and is no longer writing to,
+/// may be moved
+fn ensure_orphan(
+   app_handle: &tauri::AppHandle,
+   path: &Path,
+) -> Result<(), OrphanError> {
+   let cache_dir = app_handle.path().cache_dir()?;
+   let archive_dir = archive_dir(app_handle)?;
+
+   let in_known_dir = path
+      .parent()
+      .is_some_and(|parent| parent == cache_dir || parent == archive_dir);
+   let is_active = app_handle
+      .state::<RecordingStateMutex>()
+      .lock()
+      .map(|state| state.note_file_path.as_deref().map(Path::new) == Some(path))
+      .unwrap_or(true);
+   if !in_known_dir
+      || is_active
+      || temp_note_timestamp(path).is_none()
+      || !path.is_file()
+   {
+      return Err(OrphanError::NotOrphaned(path.display().to_string()));
+   }
+
+   Ok(())
+}
+
+fn current_settings(
+   app_handle: &tauri::AppHandle,
+) -> Result<AppSettingsState, OrphanError> {
+   app_handle
+      .state::<std::sync::Mutex<AppSettingsState>>()
+      .lock()
+      .map(|settings| settings.clone())
+      .map_err(|_| OrphanError::Settings)
+}
+
+fn archive_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, OrphanError> {
+   Ok(app_handle.path().app_data_dir()?.join(ARCHIVE_DIR))
+}
+
+fn record_directory(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+   app_handle
+      .state::<GlobalState>()
+      .record_directory
+      .lock()
+      .ok()
+      .and_then(|directory| directory.clone())
+      .map(PathBuf::from)
+}
+
+fn temp_note_files(directory: &Path) -> Vec<PathBuf> {
+   let Ok(entries) = std::fs::read_dir(directory) else {
+      return Vec::new();
+   };
+
+   entries
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| temp_note_timestamp(path).is_some())
+      .collect()
+}
+
+/// Recording start in milliseconds, from a `temp_recording_<ms>.txt` name
+fn temp_note_timestamp(path: &Path) -> Option<i64> {
+   path
+      .file_name()?
+      .to_str()?
+      .strip_prefix(TEMP_NOTE_PREFIX)?
+      .strip_suffix(".txt")?
+      .parse()
+      .ok()
+}
+
+/// Recordings without a note file of their own, with their creation time
+fn unclaimed_recordings(directory: &Path) -> Vec<(PathBuf, i64)> {
+   let Ok(entries) = std::fs::read_dir(directory) else {
+      return Vec::new();
+   };
+
+   entries
+      .flatten()
+      .map(|entry| entry.path())
+      .filter(|path| {
+         path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| RECORDING_EXTENSIONS.contains(&ext))
+      })
+      .filter(|path| {
+         !path.with_extension("txt").exists()
+            && !path.with_extension("notes.json").exists()
+      })
+      .filter_map(|path| {
+         let created =
+            std::fs::metadata(&path).and_then(|m| m.created()).ok()?;
+         Some((path, DateTime::<Utc>::from(created).timestamp_millis()))
+      })
+      .collect()
+}
+
+fn closest_recording(
+   recordings: &[(PathBuf, i64)],
+   started_at: i64,
+) -> Option<String> {
+   recordings
+      .iter()
+      .map(|(path, created)| (path, (created - started_at).abs()))
+      .filter(|(_, distance)| *distance <= MATCH_TOLERANCE_MS)
+      .min_by_key(|(_, distance)| *distance)
+      .map(|(path, _)| path.to_string_lossy().to_string())
+}
+
+/// Rename, falling back to copying when the cache directory is on another
+/// drive than the recordings
+fn move_file(from: &Path, to: &Path) -> Result<(), std::io::Error> {
+   if std::fs::rename(from, to).is_ok() {
+      return Ok(());
+   }
+
+   std::fs::copy(from, to)?;
+   std::fs::remove_file(from)
+}
@@ -1,6 +1,8 @@
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_positioner::WindowExt;
 
+use super::models::ShortcutEvents;
+
 pub fn show_window(
    app_handle: &AppHandle,
    window_label: &str,
@@ -20,3 +22,8 @@ pub fn show_window(
       let _ = win.set_focus();
    }
 }
+
+/// Tell the UI a shortcut did nothing, as no window shows it otherwise
+pub fn emit_failure(app_handle: &AppHandle, message: String) {
+   let _ = app_handle.emit(ShortcutEvents::Failed.as_ref(), message);
+}
@@ -1,15 +1,33 @@
-use tauri::AppHandle;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use std::sync::Mutex;
+
+use log::{error, info, warn};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{
+   GlobalShortcutExt, Shortcut, ShortcutState,
+};
+
+use crate::{
+   note_capture::{commands::capture_snippet, models::NoteSnippet},
+   state::AppSettingsState,
+};
 
 mod actions;
 pub mod commands;
 mod models;
 
-pub fn register_shortcuts(app_handle: &AppHandle) {
-   let shortcuts = vec![
+fn app_shortcuts() -> Vec<models::AppShortcut> {
+   vec![
       models::AppShortcut::CaptureNote,
       models::AppShortcut::OpenConfiguration,
-   ];
+      models::AppShortcut::UndoLastNote,
+      models::AppShortcut::MarkIn,
+      models::AppShortcut::MarkOut,
+      models::AppShortcut::QuickMarker,
+   ]
+}
+
+pub fn register_shortcuts(app_handle: &AppHandle) {
+   let shortcuts = app_shortcuts();
    let shortcuts_for_closure = shortcuts.clone();
 
    let _ = app_handle.plugin(
@@ -20,11 +38,18 @@ pub fn register_shortcuts(app_handle: &AppHandle) {
             }
 
             for app_shortcut in &shortcuts_for_closure {
-               let expected = app_shortcut.default_shortcut();
-               if shortcut.mods == expected.mods && shortcut.key == expected.key
-               {
+               if is_same_shortcut(shortcut, &app_shortcut.default_shortcut()) {
                   app_shortcut.execute(app);
-                  break;
+                  return;
+               }
+            }
+
+            // Not one of ours, so it belongs to a user snippet
+            if let Some(text) = snippet_text(app, shortcut) {
+               if let Err(e) = capture_snippet(app, &text) {
+                  let message = format!("Failed to capture snippet: {}", e);
+                  warn!("{}", message);
+                  actions::emit_failure(app, message);
                }
             }
          })
@@ -35,8 +60,113 @@ pub fn register_shortcuts(app_handle: &AppHandle) {
    for app_shortcut in shortcuts {
       let shortcut = app_shortcut.default_shortcut();
       match app_handle.global_shortcut().register(shortcut) {
-         Ok(_) => println!("Registered shortcut {:?}", app_shortcut),
-         Err(e) => eprintln!("Failed to register {:?}: {:?}", app_shortcut, e),
+         Ok(_) => info!("Registered shortcut {:?}", app_shortcut),
+         Err(e) => {
+            let message =
+               format!("Failed to register {:?}: {}", app_shortcut, e);
+            error!("{}", message);
+            actions::emit_failure(app_handle, message);
+         }
+      }
+   }
+
+   let snippets = app_handle
+      .state::<Mutex<AppSettingsState>>()
+      .lock()
+      .map(|settings| settings.note_snippets.clone())
+      .unwrap_or_default();
+   for message in register_snippets(app_handle, &snippets) {
+      error!("{}", message);
+      actions::emit_failure(app_handle, message);
+   }
+}
+
+/// Replace the registered snippet shortcuts with those of `snippets`. When
+/// any cannot be registered, for instance because another app holds it,
+/// the previous shortcuts are put back and the failures returned.
+pub fn update_snippet_shortcuts(
+   app_handle: &AppHandle,
+   previous: &[NoteSnippet],
+   snippets: &[NoteSnippet],
+) -> Result<(), String> {
+   unregister_snippets(app_handle, previous);
+
+   let errors = register_snippets(app_handle, snippets);
+   if errors.is_empty() {
+      return Ok(());
+   }
+
+   unregister_snippets(app_handle, snippets);
+   for message in register_snippets(app_handle, previous) {
+      error!("{}", message);
+      actions::emit_failure(app_handle, message);
+   }
+
+   Err(errors.join("\n"))
+}
+
+/// Whether `shortcut` is taken by one of the built-in shortcuts
+pub fn is_app_shortcut(shortcut: &Shortcut) -> bool {
+   app_shortcuts().iter().any(|app_shortcut| {
+      is_same_shortcut(shortcut, &app_shortcut.default_shortcut())
+   })
+}
+
+pub fn is_same_shortcut(a: &Shortcut, b: &Shortcut) -> bool {
+   a.mods == b.mods && a.key == b.key
+}
+
+/// Register each snippet's shortcut, returning the ones that failed
+fn register_snippets(
+   app_handle: &AppHandle,
+   snippets: &[NoteSnippet],
+) -> Vec<String> {
+   let mut errors = Vec::new();
+
+   for snippet in snippets {
+      let result = snippet
+         .shortcut
+         .parse::<Shortcut>()
+         .map_err(|e| e.to_string())
+         .and_then(|shortcut| {
+            app_handle
+               .global_shortcut()
+               .register(shortcut)
+               .map_err(|e| e.to_string())
+         });
+
+      match result {
+         Ok(_) => info!("Registered snippet shortcut {}", snippet.shortcut),
+         Err(e) => errors.push(format!(
+            "Failed to register snippet shortcut {}: {}",
+            snippet.shortcut, e
+         )),
       }
    }
+
+   errors
+}
+
+fn unregister_snippets(app_handle: &AppHandle, snippets: &[NoteSnippet]) {
+   for snippet in snippets {
+      if let Ok(shortcut) = snippet.shortcut.parse::<Shortcut>() {
+         let _ = app_handle.global_shortcut().unregister(shortcut);
+      }
+   }
+}
+
+fn snippet_text(app_handle: &AppHandle, shortcut: &Shortcut) -> Option<String> {
+   let settings = app_handle.state::<Mutex<AppSettingsState>>();
+   let settings = settings.lock().ok()?;
+
+   settings
+      .note_snippets
+      .iter()
+      .find(|snippet| {
+         snippet
+            .shortcut
+            .parse::<Shortcut>()
+            .is_ok_and(|parsed| is_same_shortcut(shortcut, &parsed))
+      })
+      .map(|snippet| snippet.text.clone())
 }
@@ -1,9 +1,15 @@
-use strum::EnumString;
+use log::warn;
+use strum::{AsRefStr, EnumString};
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
 use tauri_plugin_positioner::Position;
 
-use crate::{constants::WindowLabel, positioner::WindowTrayExt, WindowEvent};
+use crate::{
+   constants::WindowLabel,
+   note_capture::commands::{mark_range, quick_marker, RangeMark},
+   positioner::WindowTrayExt,
+   WindowEvent,
+};
 
 #[derive(EnumString, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AppShortcut {
@@ -11,6 +17,20 @@ pub enum AppShortcut {
    CaptureNote,
    #[strum(serialize = "open_configuration")]
    OpenConfiguration,
+   #[strum(serialize = "undo_last_note")]
+   UndoLastNote,
+   #[strum(serialize = "mark_in")]
+   MarkIn,
+   #[strum(serialize = "mark_out")]
+   MarkOut,
+   #[strum(serialize = "quick_marker")]
+   QuickMarker,
+}
+
+#[derive(EnumString, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutEvents {
+   #[strum(serialize = "shortcut:failed")]
+   Failed,
 }
 
 #[derive(serde::Serialize)]
@@ -25,6 +45,10 @@ impl AppShortcut {
       match self {
          Self::CaptureNote => "Capture Note",
          Self::OpenConfiguration => "Open Configuration",
+         Self::UndoLastNote => "Undo Last Note",
+         Self::MarkIn => "Mark In",
+         Self::MarkOut => "Mark Out",
+         Self::QuickMarker => "Quick Marker",
       }
    }
 
@@ -32,6 +56,12 @@ impl AppShortcut {
       match self {
          AppShortcut::CaptureNote => Some("When recording in progress."),
          AppShortcut::OpenConfiguration => None,
+         AppShortcut::UndoLastNote => Some("When recording in progress."),
+         AppShortcut::MarkIn => Some("Start a range note."),
+         AppShortcut::MarkOut => Some("End the open range note."),
+         AppShortcut::QuickMarker => {
+            Some("Timestamp only, add the text later.")
+         }
       }
    }
 
@@ -45,6 +75,22 @@ impl AppShortcut {
             Some(Modifiers::CONTROL | Modifiers::ALT),
             Code::Minus,
          ),
+         Self::UndoLastNote => Shortcut::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT),
+            Code::Backspace,
+         ),
+         Self::MarkIn => Shortcut::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT),
+            Code::BracketLeft,
+         ),
+         Self::MarkOut => Shortcut::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT),
+            Code::BracketRight,
+         ),
+         Self::QuickMarker => Shortcut::new(
+            Some(Modifiers::CONTROL | Modifiers::ALT),
+            Code::Enter,
+         ),
       }
    }
 
@@ -102,6 +148,37 @@ impl AppShortcut {
                )
             }
          }
+         Self::UndoLastNote => {
+            if let Err(err) =
+               crate::note_capture::commands::undo_last_note(app_handle)
+            {
+               let message = format!("Failed to undo last note: {}", err);
+               warn!("{}", message);
+               super::actions::emit_failure(app_handle, message);
+            }
+         }
+         Self::MarkIn | Self::MarkOut => {
+            let mark = if *self == Self::MarkIn {
+               RangeMark::In
+            } else {
+               RangeMark::Out
+            };
+
+            if let Err(err) =
+               mark_range(app_handle, mark, "", chrono::Utc::now())
+            {
+               let message = format!("Failed to mark {:?}: {}", mark, err);
+               warn!("{}", message);
+               super::actions::emit_failure(app_handle, message);
+            }
+         }
+         Self::QuickMarker => {
+            if let Err(err) = quick_marker(app_handle) {
+               let message = format!("Failed to add quick marker: {}", err);
+               warn!("{}", message);
+               super::actions::emit_failure(app_handle, message);
+            }
+         }
       }
    }
 
@@ -109,6 +186,10 @@ impl AppShortcut {
       vec![
          AppShortcut::CaptureNote.to_details(),
          AppShortcut::OpenConfiguration.to_details(),
+         AppShortcut::UndoLastNote.to_details(),
+         AppShortcut::MarkIn.to_details(),
+         AppShortcut::MarkOut.to_details(),
+         AppShortcut::QuickMarker.to_details(),
       ]
    }
 
@@ -132,6 +213,8 @@ impl AppShortcut {
       let key_str = match shortcut.key {
          Code::Equal => "=",
          Code::Minus => "-",
+         Code::BracketLeft => "[",
+         Code::BracketRight => "]",
          _ => &format!("{:?}", shortcut.key),
       };
 
@@ -1,16 +1,31 @@
 use std::sync::Mutex;
 
+use log::warn;
 use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
 use tokio::sync::watch;
 
-use crate::obs_websocket_connection::models::{
-   ConnectionStatus, RecordingStatus,
+use crate::{
+   constants::Store,
+   note_capture::{
+      exporters::TEXT_ID,
+      models::{Note, NoteSnippet, TagStyles, UndoneNote},
+      templates::{validate_line_template, DEFAULT_LINE_TEMPLATE},
+   },
+   obs_websocket_connection::models::{
+      ConnectionStatus, FrameRate, Pause, RecordingStatus,
+   },
 };
 
 pub struct GlobalState {
    pub server_connection_status:
       Mutex<crate::obs_websocket_connection::models::ConnectionStatus>,
    pub server_config_changed_tx: watch::Sender<()>,
+   pub obs_version: Mutex<Option<String>>,
+   pub frame_rate: Mutex<Option<FrameRate>>,
+   pub current_scene: Mutex<Option<String>>,
+   /// Folder OBS records to, as last reported
+   pub record_directory: Mutex<Option<String>>,
 }
 
 impl GlobalState {
@@ -18,6 +33,10 @@ impl GlobalState {
       Self {
          server_connection_status: Mutex::new(ConnectionStatus::Disconnected),
          server_config_changed_tx: watch::channel(()).0,
+         obs_version: Mutex::new(None),
+         frame_rate: Mutex::new(None),
+         current_scene: Mutex::new(None),
+         record_directory: Mutex::new(None),
       }
    }
 }
@@ -65,12 +84,38 @@ impl ServerConfigState {
 pub struct AppSettingsState {
    pub start_at_login: bool,
    pub hide_from_capture: bool,
+   pub enabled_exporters: Vec<String>,
+   pub cue_duration_ms: u64,
+   pub ffmpeg_path: Option<String>,
+   pub note_line_template: String,
+   /// Saved line template that cannot be read back, replaced by the
+   /// default until the user picks another
+   pub rejected_note_line_template: Option<String>,
+   pub note_header_template: String,
+   pub note_footer_template: String,
+   pub tag_styles: TagStyles,
+   pub note_snippets: Vec<NoteSnippet>,
+   /// Stretches without notes longer than this are listed in the report
+   pub report_gap_threshold_ms: u64,
 }
 
 impl AppSettingsState {
    pub fn from_store<R: tauri::Runtime>(
       store: &tauri_plugin_store::Store<R>,
    ) -> Self {
+      // Templates saved before they were checked for being readable are
+      // set aside rather than used, and reported once the app is up
+      let (note_line_template, rejected_note_line_template) = match store
+         .get("note_line_template")
+         .and_then(|v| v.as_str().map(String::from))
+      {
+         Some(template) if validate_line_template(&template).is_err() => {
+            (DEFAULT_LINE_TEMPLATE.to_string(), Some(template))
+         }
+         Some(template) => (template, None),
+         None => (DEFAULT_LINE_TEMPLATE.to_string(), None),
+      };
+
       Self {
          start_at_login: store
             .get("start_at_login")
@@ -81,6 +126,49 @@ impl AppSettingsState {
             .get("hide_from_capture")
             .and_then(|v| v.as_bool())
             .unwrap_or(false),
+
+         enabled_exporters: store
+            .get("enabled_exporters")
+            .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+            .filter(|ids| !ids.is_empty())
+            .unwrap_or_else(|| vec![TEXT_ID.to_string()]),
+
+         cue_duration_ms: store
+            .get("cue_duration_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(3000),
+
+         ffmpeg_path: store
+            .get("ffmpeg_path")
+            .and_then(|v| v.as_str().map(String::from)),
+
+         note_line_template,
+         rejected_note_line_template,
+
+         note_header_template: store
+            .get("note_header_template")
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default(),
+
+         note_footer_template: store
+            .get("note_footer_template")
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default(),
+
+         tag_styles: store
+            .get("tag_styles")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+
+         note_snippets: store
+            .get("note_snippets")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+
+         report_gap_threshold_ms: store
+            .get("report_gap_threshold_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(300_000),
       }
    }
 }
@@ -89,9 +177,62 @@ impl AppSettingsState {
 pub struct RecordingState {
    pub recording_status: RecordingStatus,
    pub note_file_path: Option<String>,
+   /// Recording the notes belong to, its first file when OBS splits it
+   pub recording_path: Option<String>,
+   /// File OBS is writing to, which moves on each time it splits
+   pub output_path: Option<String>,
+   /// Rendered header, kept so the note file can be rewritten
+   pub note_file_header: String,
    pub recording_start: Option<i64>,
    pub accumulated_pause_duration: i64,
    pub pause_start: Option<i64>,
+   pub pauses: Vec<Pause>,
+   pub notes: Vec<Note>,
+   pub next_note_id: u64,
+   /// Range note marked in but not yet out
+   pub open_range_id: Option<u64>,
+   pub undone_note: Option<UndoneNote>,
+}
+
+const RECORDING_STATE_KEY: &str = "recording_state";
+
+impl RecordingState {
+   pub fn from_store<R: tauri::Runtime>(
+      store: &tauri_plugin_store::Store<R>,
+   ) -> Self {
+      store
+         .get(RECORDING_STATE_KEY)
+         .and_then(|v| serde_json::from_value(v).ok())
+         .unwrap_or_default()
+   }
+
+   /// Hand the state to the store, so a restart mid-recording carries on
+   /// with the same note file and timecodes. The store writes it to disk
+   /// on its own debounce timer rather than while the caller holds the
+   /// state lock, and flushes it when the app exits.
+   pub fn save(&self, app_handle: &tauri::AppHandle) {
+      let store = match app_handle.store(Store::RecordingState.as_ref()) {
+         Ok(store) => store,
+         Err(err) => {
+            warn!("Failed to load recording state store: {}", err);
+            return;
+         }
+      };
+
+      store.set(RECORDING_STATE_KEY, serde_json::json!(self));
+   }
+
+   /// Time recorded so far, excluding any paused time
+   pub fn elapsed_ms(&self, now: i64) -> Option<i64> {
+      let recording_start = self.recording_start?;
+      let current_pause = self.pause_start.map_or(0, |start| now - start);
+
+      Some(
+         now - recording_start
+            - self.accumulated_pause_duration
+            - current_pause,
+      )
+   }
 }
 
 pub type RecordingStateMutex = Mutex<RecordingState>;
@@ -0,0 +1,47 @@
+import { type ReactNode } from "react";
+
+import { motion, type MotionProps } from "motion/react";
+
+type PingProps = MotionProps & {
+  children: ReactNode;
+  color?: string;
+  /**
+   * Plays one ring each time it changes, none while it is 0
+   */
+  trigger: number;
+};
+
+/**
+ * @public
+ */
+export function Ping({
+  children,
+  color = "rgba(239,68,68,0.8)",
+  trigger,
+  ...props
+}: PingProps) {
+  return (
+    <motion.div {...props} className="relative">
+      {trigger > 0 && (
+        <motion.span
+          key={trigger}
+          animate={{
+            opacity: 0,
+            scale: 2,
+          }}
+          className="absolute inset-0 rounded-full border-2"
+          initial={{
+            opacity: 1,
+            scale: 1,
+          }}
+          style={{ borderColor: color }}
+          transition={{
+            duration: 0.8,
+            ease: "easeOut",
+          }}
+        />
+      )}
+      {children}
+    </motion.div>
+  );
+}
@@ -0,0 +1,71 @@
+import { useEffect, useState } from "react";
+
+import { listen } from "@tauri-apps/api/event";
+import { TriangleAlert } from "lucide-react";
+
+import { ScrollArea } from "@/components/miscellaneous/scroll-area";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/overlays/dialog";
+
+const recordingEvents = {
+  ExportWarnings: "recording:export_warnings",
+} as const;
+
+export function ExportWarnings() {
+  const [warnings, setWarnings] = useState<string[]>([]);
+
+  useEffect(() => {
+    // Warnings arrive after a recording stops, usually while the window is
+    // hidden, so they pile up until the user opens it and dismisses them
+    const unlisten = listen<string[]>(
+      recordingEvents.ExportWarnings,
+      (event) => {
+        setWarnings((current) => [...current, ...event.payload]);
+      }
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  function onOpenChange(open: boolean) {
+    if (!open) setWarnings([]);
+  }
+
+  return (
+    <Dialog onOpenChange={onOpenChange} open={warnings.length > 0}>
+      <DialogContent
+        className={`
+          flex max-h-[calc(100%-4rem)] max-w-95 flex-col gap-2 p-4
+        `}
+      >
+        <DialogHeader>
+          <DialogTitle
+            className={`
+              flex items-center gap-2 text-base text-amber-400
+            `}
+          >
+            <TriangleAlert className="size-4" />
+            Export Warnings
+          </DialogTitle>
+          <DialogDescription>
+            Some notes could not be exported as expected
+          </DialogDescription>
+        </DialogHeader>
+        <ScrollArea className="h-full" orientation="vertical" insetShadow>
+          <ul className="flex flex-col gap-1.5 px-2.5 py-2 text-sm">
+            {warnings.map((warning, index) => (
+              <li key={index}>{warning}</li>
+            ))}
+          </ul>
+        </ScrollArea>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,66 @@
+import { useEffect, useState } from "react";
+
+import { listen } from "@tauri-apps/api/event";
+import { TriangleAlert } from "lucide-react";
+
+import { ScrollArea } from "@/components/miscellaneous/scroll-area";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/overlays/dialog";
+
+const shortcutEvents = {
+  Failed: "shortcut:failed",
+} as const;
+
+export function ShortcutFailures() {
+  const [failures, setFailures] = useState<string[]>([]);
+
+  useEffect(() => {
+    // Shortcuts are pressed while the window is hidden, so failures pile
+    // up until the user opens it and dismisses them
+    const unlisten = listen<string>(shortcutEvents.Failed, (event) => {
+      setFailures((current) => [...current, event.payload]);
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  function onOpenChange(open: boolean) {
+    if (!open) setFailures([]);
+  }
+
+  return (
+    <Dialog onOpenChange={onOpenChange} open={failures.length > 0}>
+      <DialogContent
+        className={`
+          flex max-h-[calc(100%-4rem)] max-w-95 flex-col gap-2 p-4
+        `}
+      >
+        <DialogHeader>
+          <DialogTitle
+            className={`
+              flex items-center gap-2 text-base text-amber-400
+            `}
+          >
+            <TriangleAlert className="size-4" />
+            Shortcut Failed
+          </DialogTitle>
+          <DialogDescription>Some shortcuts did nothing</DialogDescription>
+        </DialogHeader>
+        <ScrollArea className="h-full" orientation="vertical" insetShadow>
+          <ul className="flex flex-col gap-1.5 px-2.5 py-2 text-sm">
+            {failures.map((failure, index) => (
+              <li key={index}>{failure}</li>
+            ))}
+          </ul>
+        </ScrollArea>
+      </DialogContent>
+    </Dialog>
+  );
+}
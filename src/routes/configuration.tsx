@@ -4,7 +4,9 @@ import { createFileRoute } from "@tanstack/react-router";
 
 import { DotPattern } from "@/components/miscellaneous/dot-pattern";
 import { ConnectionStatus } from "@/features/connection-status/connection-status";
+import { ExportWarnings } from "@/features/export-warnings/export-warnings";
 import { ObsWebsocketConfiguration } from "@/features/obs-websocket-configuration/obs-websocket-configuration";
+import { ShortcutFailures } from "@/features/shortcuts/shortcut-failures";
 import { Shortcuts } from "@/features/shortcuts/shortcuts";
 import { WindowUtilities } from "@/features/window-utilities/window-utilities";
 
@@ -39,6 +41,8 @@ function Configuration() {
         `}
       />
       <WindowUtilities className="col-span-2" />
+      <ExportWarnings />
+      <ShortcutFailures />
     </div>
   );
 }
@@ -9,6 +9,7 @@ import z from "zod";
 
 import { Breathe } from "@/components/motion/breathe";
 import { Flip } from "@/components/motion/flip";
+import { Ping } from "@/components/motion/ping";
 import { ShadowPulse } from "@/components/motion/shadow-pulse";
 
 export const Route = createFileRoute("/recording-status")({
@@ -16,6 +17,8 @@ export const Route = createFileRoute("/recording-status")({
 });
 
 const recordingEvents = {
+  MarkerAdded: "recording:marker_added",
+  NoRangeOpen: "recording:no_range_open",
   Status: "recording:status",
 } as const;
 
@@ -36,6 +39,8 @@ async function getRecordingStatus(): Promise<RecordingStatus> {
 
 function RouteComponent() {
   const [status, setStatus] = useState<RecordingStatus>();
+  const [markerCount, setMarkerCount] = useState(0);
+  const [markerColor, setMarkerColor] = useState<string>();
 
   useEffect(() => {
     getRecordingStatus().then((initialStatus) => {
@@ -49,8 +54,22 @@ function RouteComponent() {
       }
     );
 
+    // Notes added without opening a window only show up as a ring
+    const unlistenMarker = listen(recordingEvents.MarkerAdded, () => {
+      setMarkerColor(undefined);
+      setMarkerCount((count) => count + 1);
+    });
+
+    // Marking out with no range open still adds the note, as a point
+    const unlistenNoRange = listen(recordingEvents.NoRangeOpen, () => {
+      setMarkerColor("rgba(251,191,36,0.8)");
+      setMarkerCount((count) => count + 1);
+    });
+
     return () => {
       unlisten.then((f) => f());
+      unlistenMarker.then((f) => f());
+      unlistenNoRange.then((f) => f());
     };
   }, []);
 
@@ -68,12 +87,14 @@ function RouteComponent() {
         {status?.active && !status?.paused && (
           <Flip key="recording">
             <Breathe>
-              <Circle
-                className={`
-                  fill-red-500 stroke-red-500 text-6xl
-                  drop-shadow-[0_0_8px_rgba(239,68,68,0.6)]
-                `}
-              />
+              <Ping color={markerColor} trigger={markerCount}>
+                <Circle
+                  className={`
+                    fill-red-500 stroke-red-500 text-6xl
+                    drop-shadow-[0_0_8px_rgba(239,68,68,0.6)]
+                  `}
+                />
+              </Ping>
             </Breathe>
           </Flip>
         )}